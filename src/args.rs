@@ -1,6 +1,9 @@
-use clap::Parser;
+use clap::{Parser, Subcommand};
 
-use crate::{tomlConf::configStruct::ErrorLevel, ARGS};
+use crate::{
+    tomlConf::configStruct::{ErrorLevel, Runtime},
+    ARGS,
+};
 
 #[derive(Debug, clap::ValueEnum, Clone, Default)]
 pub enum RunAfter {
@@ -19,7 +22,7 @@ impl RunAfter {
 }
 
 /// Updater for Reframework mod games
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Default)]
 #[clap(author, version, about, long_about = None)]
 pub struct ArgsClap {
     /// Only performs update for one game mod and launch it later by default
@@ -33,6 +36,163 @@ pub struct ArgsClap {
     /// combined with one update
     #[clap(short, long, value_enum, default_value = "yes")]
     pub run: RunAfter,
+
+    /// combined with one installs given release instead of the latest one. Accepts a tag or latest, previous, N-<number>
+    #[clap(long, value_parser)]
+    pub release: Option<String>,
+
+    /// combined with one or launch appends given arguments to runArgs of the game for this run only i.e. --game-args="-dx11"
+    #[clap(long, value_parser, allow_hyphen_values = true)]
+    pub game_args: Option<String>,
+
+    #[clap(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Non-interactive commands. None of them opens a dialog
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Prints installed mod version, type, runtime and cached versions per game
+    Status {
+        /// Output format. json and toml are meant for scripts, logs go to stderr then
+        #[clap(long, value_enum, default_value = "text")]
+        format: StatusFormat,
+    },
+    /// Updates mod for given game or for all games that require an update
+    Update {
+        /// Game short name i.e. RE2
+        #[clap(value_parser, required_unless_present = "all")]
+        game: Option<String>,
+        /// Update all games that require an update
+        #[clap(long, value_parser, conflicts_with = "game")]
+        all: bool,
+        /// Mod type for games supporting both types which don't have mod installed yet
+        #[clap(long, value_enum)]
+        prefer: Option<PreferType>,
+    },
+    /// Switches mod runtime for the game
+    SwitchRuntime {
+        #[clap(value_parser)]
+        game: String,
+        #[clap(value_enum)]
+        runtime: RuntimeArg,
+    },
+    /// Switches mod type between Nextgen/Standard for the game
+    SwitchType {
+        #[clap(value_parser)]
+        game: String,
+    },
+    /// Loads given mod version from cache for the game
+    LoadCache {
+        #[clap(value_parser)]
+        game: String,
+        #[clap(value_parser)]
+        version: String,
+    },
+    /// Rescans local mod settings of all games
+    Rescan,
+    /// Launches the game without checking for mod update
+    Launch {
+        #[clap(value_parser)]
+        game: String,
+    },
+    /// Registers game folder manually i.e. for non-Steam or custom installs. Such game is launched via its executable
+    Register {
+        /// Game short name i.e. RE2
+        #[clap(value_parser)]
+        game: String,
+        /// Game folder containing the game executable
+        #[clap(value_parser)]
+        path: String,
+    },
+    /// Works with config.toml file
+    Config {
+        #[clap(subcommand)]
+        action: ConfigAction,
+    },
+    /// Restores REFresher executable replaced by the last self-update, kept as <executable>.old
+    Rollback,
+    /// Works with cached mod versions in refr_cache folder
+    Cache {
+        #[clap(subcommand)]
+        action: CacheAction,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum CacheAction {
+    /// Removes cached versions over max_cache_versions_per_game, max_cache_size_mb or max_cache_age_days.
+    /// Version in use, pinned ones and ones with keep = true are never removed
+    Prune {
+        /// Only prints what would be removed
+        #[clap(long, value_parser)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigAction {
+    /// Validates config.toml and prints every problem found. Nothing else is run and the file is not changed
+    Check,
+    /// Replaces config.toml with its backup config.toml.bak.<NUMBER>, 1 is the newest one
+    Restore {
+        #[clap(value_parser, default_value = "1")]
+        number: u8,
+    },
+    /// Prints value of the key i.e. autoupdate or RE2.runArgs, keys without section are from [main]
+    Get {
+        #[clap(value_parser)]
+        key: String,
+    },
+    /// Sets value of the key after checking it is valid for that key. Comments and the rest of the file are kept
+    Set {
+        #[clap(value_parser)]
+        key: String,
+        /// TOML value i.e. true, 3 or ["nightly"]. Anything else is taken as a string
+        #[clap(value_parser, allow_hyphen_values = true)]
+        value: String,
+    },
+}
+
+#[derive(Debug, clap::ValueEnum, Clone, PartialEq, Eq)]
+pub enum StatusFormat {
+    text,
+    json,
+    toml,
+}
+
+#[derive(Debug, clap::ValueEnum, Clone, PartialEq, Eq)]
+pub enum PreferType {
+    standard,
+    nextgen,
+}
+
+impl PreferType {
+    pub fn is_nextgen(&self) -> bool {
+        match self {
+            PreferType::standard => false,
+            PreferType::nextgen => true,
+        }
+    }
+}
+
+#[derive(Debug, clap::ValueEnum, Clone, PartialEq, Eq)]
+pub enum RuntimeArg {
+    openxr,
+    openvr,
+}
+
+impl RuntimeArg {
+    pub fn to_runtime(&self) -> Runtime {
+        match self {
+            RuntimeArg::openxr => Runtime::OpenXR,
+            RuntimeArg::openvr => Runtime::OpenVR,
+        }
+    }
+}
+
+pub fn get_command() -> Option<Command> {
+    unsafe { ARGS.as_ref().and_then(|args| args.command.clone()) }
 }
 
 pub unsafe fn parse_args() {
@@ -5,7 +5,7 @@ use std::{collections::HashMap, error::Error};
 
 use error_stack::{Report, Result, ResultExt};
 use log::{debug, info, warn};
-use self_update::update::ReleaseAsset;
+use self_update::update::{Release, ReleaseAsset};
 
 #[cfg(test)]
 use crate::utils::open_dialog::mock_open_dialog as open_dialog;
@@ -13,17 +13,20 @@ use crate::utils::open_dialog::mock_open_dialog as open_dialog;
 use crate::utils::open_dialog::open_dialog;
 use crate::{
     dialogs::dialogs_label::{LabelOptions, SWITCH_RUNTIME_PART},
-    rManager::{
-        rManager::UPDATE_IDENTIFIER,
-        rManager_header::{REvilManager, REvilManagerState, ResultManagerErr, SORT_DETERMINER},
+    rManager::rManager_header::{
+        REvilManager, REvilManagerState, ResultManagerErr, SORT_DETERMINER,
+    },
+    reframework_github::{refr_github::AssetsReport, release_source::get_game_channel},
+    tomlConf::configStruct::{
+        Edition, GameConfig, PendingAction, REvilConfig, Runtime, ShortGameName, SteamId,
     },
-    reframework_github::refr_github::AssetsReport,
-    tomlConf::configStruct::{REvilConfig, ShortGameName, SteamId},
     utils::{
         find_game_conf_by_steam_id::find_game_conf_by_steam_id,
-        get_local_path_to_cache::get_local_path_to_cache_folder, is_asset_tdb::is_asset_tdb,
+        game_registry::{game_supports_both_editions, with_game_registry},
+        get_local_path_to_cache::get_local_path_to_cache_folder,
+        is_asset_tdb::is_asset_tdb,
     },
-    GAMES_NEXTGEN_SUPPORT, STANDARD_TYPE_QUALIFIER,
+    STANDARD_TYPE_QUALIFIER,
 };
 
 #[derive(PartialEq, Debug, Default)]
@@ -72,6 +75,11 @@ pub trait Ask {
         config: &REvilConfig,
         state: &REvilManagerState,
     ) -> ResultDialogsErr<SwitchActionReport>;
+    fn get_selected_release(&mut self, releases: &[Release], has_more: bool) -> LabelOptions;
+    fn get_game_for_release(&mut self, config: &REvilConfig) -> Option<ShortGameName>;
+    fn get_pin_decision(&mut self, config: &REvilConfig) -> LabelOptions;
+    fn get_uninstall_decision(&mut self, config: &REvilConfig) -> LabelOptions;
+    fn get_game_to_register(&mut self, config: &REvilConfig) -> Option<(ShortGameName, String)>;
 }
 
 pub struct Dialogs;
@@ -168,8 +176,11 @@ impl Ask for Dialogs {
         let mut selections_h_map: HashMap<String, &SteamId> = HashMap::new();
         let mut any_game_that_support_2_versions = false;
         config.games.iter().for_each(|(short_name, game_config)| {
-            if !any_game_that_support_2_versions && GAMES_NEXTGEN_SUPPORT.contains(&&short_name[..])
-            {
+            // sections only overriding the game registry have nothing to launch
+            if game_config.location.is_none() {
+                return;
+            }
+            if !any_game_that_support_2_versions && game_supports_both_editions(short_name) {
                 any_game_that_support_2_versions = true;
             }
             let mut ver_in_use = game_config
@@ -177,22 +188,15 @@ impl Ask for Dialogs {
                 .as_ref()
                 .map(|ver| ver.to_string())
                 .unwrap_or_default();
-            if game_config
-                .versions
-                .as_ref()
-                .unwrap()
-                .iter()
-                .skip(1)
-                .any(|ver_set| {
-                    ver_set
-                        .first()
-                        .map(|ver| ver == game_config.version_in_use.as_ref().unwrap())
-                        .unwrap_or_default()
-                        && ver_set.len() > 1
-                })
-            {
+            if game_config.versions.iter().flatten().skip(1).any(|record| {
+                Some(&record.version) == game_config.version_in_use.as_ref()
+                    && !record.assets.is_empty()
+            }) {
                 ver_in_use = format!("{} <no_latest_cache>", ver_in_use);
             }
+            if game_config.pin.is_some() {
+                ver_in_use = format!("{} <pinned>", ver_in_use);
+            }
             // no_latest_cache means that version has cache files but it is not latest one
             selections_h_map.insert(
                 format!(
@@ -216,8 +220,15 @@ impl Ask for Dialogs {
         });
         let mut selections: Vec<String> = selections_h_map.keys().cloned().collect();
         selections.sort();
+        if let Some(release) = &state.self_update_release {
+            selections.push(SelfUpdate(release.version.clone()).to_label());
+        }
         selections.push(SwitchRuntimeSection.to_label());
         selections.push(LoadDifferentVersionFromCache.to_label());
+        selections.push(InstallDifferentRelease.to_label());
+        selections.push(PinSection.to_label());
+        selections.push(UninstallSection.to_label());
+        selections.push(RegisterGameSection.to_label());
         if any_game_that_support_2_versions {
             selections.push(SwitchType.to_label());
         }
@@ -248,6 +259,26 @@ impl Ask for Dialogs {
                 state.selected_option = Some(LoadDifferentVersionFromCache);
                 return Ok(());
             }
+            InstallDifferentRelease => {
+                state.selected_option = Some(InstallDifferentRelease);
+                return Ok(());
+            }
+            PinSection => {
+                state.selected_option = Some(PinSection);
+                return Ok(());
+            }
+            UninstallSection => {
+                state.selected_option = Some(UninstallSection);
+                return Ok(());
+            }
+            RegisterGameSection => {
+                state.selected_option = Some(RegisterGameSection);
+                return Ok(());
+            }
+            SelfUpdate(version) => {
+                state.selected_option = Some(SelfUpdate(version));
+                return Ok(());
+            }
             GoTop => {
                 state.selected_option = Some(GoTop);
                 return Ok(());
@@ -280,11 +311,11 @@ impl Ask for Dialogs {
                 return;
             }
             let versions = versions.unwrap();
-            for ver_set in versions.iter() {
-                if ver_set.len() < 2 {
+            for record in versions.iter() {
+                if record.assets.is_empty() {
                     continue;
                 }
-                let ver = ver_set.first().unwrap();
+                let ver = &record.version;
                 let mut label_appendix: String = "".to_string();
                 if game_config
                     .version_in_use
@@ -294,8 +325,13 @@ impl Ask for Dialogs {
                 {
                     label_appendix = format!("{SORT_DETERMINER} this is your current version - ");
                 }
-                ver_set.iter().skip(1).for_each(|asset_name| {
-                    match get_local_path_to_cache_folder(None, Some(ver)) {
+                record.assets.iter().for_each(|asset| {
+                    let asset_name = &asset.name;
+                    match get_local_path_to_cache_folder(
+                        None,
+                        Some(ver),
+                        get_game_channel(game_config),
+                    ) {
                         Ok(folder) => {
                             if !folder.join(asset_name).exists() {
                                 return;
@@ -344,15 +380,16 @@ impl Ask for Dialogs {
             .games
             .iter()
             .filter_map(|(short_name, game)| {
-                if !GAMES_NEXTGEN_SUPPORT.contains(&&short_name[..]) {
+                if !game_supports_both_editions(short_name) {
                     debug!("Game doesn't support both versions {}", short_name);
                     return None;
                 }
-                if game.versions.is_none() || game.version_in_use.is_none() {
+                let latest_record = game.versions.as_ref().and_then(|versions| versions.first());
+                if latest_record.is_none() || game.version_in_use.is_none() {
                     info!("Is mod installed for {}?", short_name);
                     return None;
                 }
-                if game.versions.as_ref().unwrap().first().unwrap().first().unwrap() != game.version_in_use.as_ref().unwrap() {
+                if latest_record.map(|record| &record.version) != game.version_in_use.as_ref() {
                     info!(r"Switch type decision only supports latest cached versions.
                      If you want to switch to older version then use load from cache and select appropriate one.
                       Game {}", short_name);
@@ -380,49 +417,169 @@ impl Ask for Dialogs {
             }
             SwitchToStandard(short_name) | SwitchToNextgen(short_name) => {
                 debug!("Selected -> {:#?}", short_name);
-                let game_config = config.games.get(&short_name).unwrap();
-
-                if !state.games_that_require_update.contains(&short_name) {
-                    let next_gen = game_config.nextgen.unwrap();
-                    let versions = game_config.versions.as_ref().unwrap();
-                    let first_set = versions.first().unwrap();
-
-                    let second_asset_name = first_set.iter().skip(1).find(|name| {
-                        is_asset_tdb(
-                            &short_name,
-                            &ReleaseAsset {
-                                name: name.to_string(),
-                                ..Default::default()
-                            },
-                        )
-                        // we want other type so condition below has to be different than usually
-                        .map(|is_tdb| ((is_tdb && next_gen) || (!is_tdb && !next_gen)))
-                        .unwrap_or_default()
-                    });
+                return get_switch_type_action(config, state, short_name);
+            }
+            _ => (),
+        };
+        Ok(Early)
+    }
 
-                    if let Some(second_asset_name) = second_asset_name {
-                        debug!("preparing unzip for {}", second_asset_name);
-                        let path_to_zip = get_local_path_to_cache_folder(None, Some(&first_set[0]))
-                            .map(|path| path.join(second_asset_name))
-                            .map_err(|_| Report::new(DialogsErrors::Other))?;
-                        if !path_to_zip.exists() {
-                            return Ok(UnsetNonExistentToggleNRestart(
-                                short_name,
-                                second_asset_name.to_string(),
-                            ));
-                        }
-                        return Ok(ToggleNUnzipSave(short_name, second_asset_name.clone()));
-                    }
-                } else {
-                    debug!("Game {} requires update anyway", short_name);
-                    return Ok(ToggleNSaveRestart(short_name));
+    fn get_selected_release(&mut self, releases: &[Release], has_more: bool) -> LabelOptions {
+        let mut selections: Vec<String> = releases
+            .iter()
+            .map(|release| SelectRelease(release.name.to_string()).to_label())
+            .collect();
+        selections.insert(0, Back.to_label());
+        if has_more {
+            selections.push(MoreReleases.to_label());
+        }
+        let selection = match open_dialog::open_dialog(
+            &selections,
+            "Select release to install. Newest releases are on top",
+            Some(MAX_LENGTH_FOR_CACHE_LABELS),
+        ) {
+            Ok(it) => it,
+            Err(err) => {
+                warn!("{}", err);
+                debug!("{:#?}", err);
+                return Back;
+            }
+        };
+        LabelOptions::from(&selections[selection][..])
+    }
+
+    // releases are listed afterwards from the game channel, so the game is selected first
+    fn get_game_for_release(&mut self, config: &REvilConfig) -> Option<ShortGameName> {
+        let mut selections: Vec<String> = config
+            .games
+            .iter()
+            .filter(|(_, game_config)| game_config.location.is_some())
+            .map(
+                |(short_name, game_config)| match get_game_channel(game_config) {
+                    Some(channel) => format!("{} - {} channel", short_name, channel),
+                    None => short_name.to_string(),
+                },
+            )
+            .collect();
+        selections.sort();
+        selections.push(Back.to_label());
+        let selection = match open_dialog::open_dialog(
+            &selections,
+            "Select game to install a different release for",
+            None,
+        ) {
+            Ok(it) => it,
+            Err(err) => {
+                warn!("{}", err);
+                debug!("{:#?}", err);
+                return None;
+            }
+        };
+        if LabelOptions::from(&selections[selection][..]) == Back {
+            return None;
+        }
+        selections[selection].split(" - ").next().map(String::from)
+    }
+
+    fn get_pin_decision(&mut self, config: &REvilConfig) -> LabelOptions {
+        let mut selections: Vec<String> = config
+            .games
+            .iter()
+            .filter_map(|(short_name, game_config)| {
+                if game_config.pin.is_some() {
+                    return Some(Unpin(short_name.to_string()).to_label());
                 }
+                game_config.version_in_use.as_ref().map(|version| {
+                    PinVersion(short_name.to_string(), version.to_string()).to_label()
+                })
+            })
+            .collect();
+        selections.sort();
+        selections.push(Back.to_label());
+        let selection = match open_dialog::open_dialog(
+            &selections,
+            "Select game to pin to its current mod version or to unpin. Pinned games are not updated",
+            None,
+        ) {
+            Ok(it) => it,
+            Err(err) => {
+                warn!("{}", err);
+                debug!("{:#?}", err);
+                return Back;
+            }
+        };
+        LabelOptions::from(&selections[selection][..])
+    }
 
-                return Ok(ToggleNSetSwitchSaveRestart(short_name));
+    fn get_uninstall_decision(&mut self, config: &REvilConfig) -> LabelOptions {
+        let mut selections: Vec<String> = config
+            .games
+            .iter()
+            .filter(|(_, game_config)| game_config.version_in_use.is_some())
+            .map(|(short_name, _)| Uninstall(short_name.to_string()).to_label())
+            .collect();
+        selections.sort();
+        selections.push(Back.to_label());
+        let selection = match open_dialog::open_dialog(
+            &selections,
+            "Select game to remove REFramework mod from. Only mod files are removed",
+            None,
+        ) {
+            Ok(it) => it,
+            Err(err) => {
+                warn!("{}", err);
+                debug!("{:#?}", err);
+                return Back;
             }
-            _ => (),
         };
-        Ok(Early)
+        LabelOptions::from(&selections[selection][..])
+    }
+
+    fn get_game_to_register(&mut self, config: &REvilConfig) -> Option<(ShortGameName, String)> {
+        let mut selections: Vec<String> = with_game_registry(|registry| {
+            registry
+                .games
+                .iter()
+                .map(|entry| match config.games.get(&entry.short_name) {
+                    Some(game_config) => format!(
+                        "{} - {}",
+                        entry.short_name,
+                        game_config.location.as_deref().unwrap_or_default()
+                    ),
+                    None => entry.short_name.to_string(),
+                })
+                .collect()
+        });
+        selections.sort();
+        selections.push(Back.to_label());
+        let selection = match open_dialog::open_dialog(
+            &selections,
+            "Select game to register its folder manually i.e. for non-Steam or custom installs",
+            None,
+        ) {
+            Ok(it) => it,
+            Err(err) => {
+                warn!("{}", err);
+                debug!("{:#?}", err);
+                return None;
+            }
+        };
+        if LabelOptions::from(&selections[selection][..]) == Back {
+            return None;
+        }
+        let short_name = selections[selection]
+            .split(" - ")
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        match open_dialog::open_input(&format!("Folder of {} game", short_name)) {
+            Ok(location) => Some((short_name, location)),
+            Err(err) => {
+                warn!("{}", err);
+                debug!("{:#?}", err);
+                None
+            }
+        }
     }
 
     fn ask_for_runtime_decision_and_change_it(
@@ -464,45 +621,109 @@ impl Ask for Dialogs {
         let game_config = config.games.get_mut(&game_short_name).unwrap();
         state.selected_option = Some(Back);
 
-        if let Some(runtime) = game_config.runtime.as_ref() {
-            info!(
-                "Switched runtime from {:?} to {:?} for {}",
-                runtime,
-                runtime.as_opposite(),
-                game_short_name
-            );
-            game_config.runtime = Some(runtime.as_opposite());
-        }
-
-        // TODO below doesn't check if asset is tdb/non-tdb only get 1st asset position
-        //      it shouldn't be problem but in case added TODO
-        if let Some(pos) = game_config
-            .versions
+        let runtime = game_config
+            .runtime
             .as_ref()
-            .unwrap()
+            .map(|runtime| runtime.as_opposite())
+            .ok_or_else(|| Report::new(DialogsErrors::Other))?;
+        set_runtime_and_find_cached_version(game_config, &game_short_name, runtime)
+            .map(|pos| Some((pos, game_short_name)))
+    }
+}
+
+// decides how to switch mod type of the game: from cached asset of the other type or by downloading it
+pub fn get_switch_type_action(
+    config: &REvilConfig,
+    state: &REvilManagerState,
+    short_name: ShortGameName,
+) -> ResultDialogsErr<SwitchActionReport> {
+    use SwitchActionReport::*;
+    let game_config = config.games.get(&short_name).unwrap();
+
+    let first_set = game_config
+        .versions
+        .as_ref()
+        .and_then(|versions| versions.first());
+    if let (false, Some(first_set)) = (
+        state.games_that_require_update.contains(&short_name),
+        first_set,
+    ) {
+        let next_gen = game_config.nextgen.unwrap();
+
+        // we want asset of the other type
+        let other_edition = match next_gen {
+            true => Edition::standard,
+            false => Edition::nextgen,
+        };
+        let second_asset_name = first_set
+            .assets
             .iter()
-            .position(|ver_set| {
-                ver_set
-                    .first()
-                    .map(|ver| ver == game_config.version_in_use.as_ref().unwrap())
-                    .unwrap_or_default()
-                    && ver_set.len() > 1
-            })
-        {
-            return Ok(Some((pos, game_short_name)));
-        } else {
-            info!("Mod version has no cache file I will download latest version");
-            if let Some(latest_version) = game_config
-                .versions
-                .as_mut()
-                .and_then(|versions| versions.first_mut())
-                .and_then(|ver| ver.first_mut())
-            {
-                *latest_version = UPDATE_IDENTIFIER.to_string();
+            .find(|asset| asset.edition == Some(other_edition))
+            .map(|asset| &asset.name);
+
+        if let Some(second_asset_name) = second_asset_name {
+            debug!("preparing unzip for {}", second_asset_name);
+            let path_to_zip = get_local_path_to_cache_folder(
+                None,
+                Some(&first_set.version),
+                get_game_channel(game_config),
+            )
+            .map(|path| path.join(second_asset_name))
+            .map_err(|_| Report::new(DialogsErrors::Other))?;
+            if !path_to_zip.exists() {
+                return Ok(UnsetNonExistentToggleNRestart(
+                    short_name,
+                    second_asset_name.to_string(),
+                ));
             }
+            return Ok(ToggleNUnzipSave(short_name, second_asset_name.clone()));
+        }
+    } else {
+        debug!("Game {} requires update anyway", short_name);
+        return Ok(ToggleNSaveRestart(short_name));
+    }
 
-            return Err(Report::new(DialogsErrors::NoCacheFile(game_short_name)));
+    Ok(ToggleNSetSwitchSaveRestart(short_name))
+}
+
+// sets runtime and returns position of version set with cache file of version in use
+pub fn set_runtime_and_find_cached_version(
+    game_config: &mut GameConfig,
+    game_short_name: &str,
+    runtime: Runtime,
+) -> ResultDialogsErr<usize> {
+    info!(
+        "Switched runtime from {:?} to {:?} for {}",
+        game_config.runtime, runtime, game_short_name
+    );
+    game_config.runtime = Some(runtime);
+
+    // TODO below doesn't check if asset is tdb/non-tdb only get 1st asset position
+    //      it shouldn't be problem but in case added TODO
+    if let Some(pos) = game_config
+        .versions
+        .as_ref()
+        .unwrap()
+        .iter()
+        .position(|record| {
+            Some(&record.version) == game_config.version_in_use.as_ref()
+                && !record.assets.is_empty()
+        })
+    {
+        Ok(pos)
+    } else {
+        info!("Mod version has no cache file I will download latest version");
+        if let Some(latest_record) = game_config
+            .versions
+            .as_mut()
+            .and_then(|versions| versions.first_mut())
+        {
+            latest_record.pending = Some(PendingAction::update);
         }
+
+        Err(Report::new(DialogsErrors::NoCacheFile(
+            game_short_name.to_string(),
+        )))
     }
 }
 
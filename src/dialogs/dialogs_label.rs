@@ -10,6 +10,16 @@ pub enum LabelOptions {
     SwitchRuntime,
     LoadDifferentVersionFromCache,
     LoadFromCache(ShortGameName, AssetName, Version),
+    InstallDifferentRelease,
+    MoreReleases,
+    SelectRelease(Version),
+    PinSection,
+    PinVersion(ShortGameName, Version),
+    Unpin(ShortGameName),
+    UninstallSection,
+    Uninstall(ShortGameName),
+    RegisterGameSection,
+    SelfUpdate(Version),
     Skip,
     Back,
     GoTop,
@@ -33,6 +43,11 @@ impl From<&str> for LabelOptions {
             "Switch type..." => SwitchType,
             "Switch runtime..." => SwitchRuntimeSection,
             "Load from cache..." => LoadDifferentVersionFromCache,
+            "Install different release..." => InstallDifferentRelease,
+            "More releases..." => MoreReleases,
+            "Pin version..." => PinSection,
+            "Uninstall mod..." => UninstallSection,
+            "Add game manually..." => RegisterGameSection,
             "Skip" => Skip,
             "Exit" => Exit,
             "Rescan local settings..." => RescanLocal,
@@ -45,6 +60,10 @@ impl From<&str> for LabelOptions {
 
             label => deduct_switch_to(label)
                 .or_else(|| deduct_load_from_cache(label))
+                .or_else(|| deduct_release(label))
+                .or_else(|| deduct_pin(label))
+                .or_else(|| deduct_uninstall(label))
+                .or_else(|| deduct_self_update(label))
                 .or_else(|| label.contains(SWITCH_RUNTIME_PART).then_some(SwitchRuntime))
                 .unwrap_or(Other),
         }
@@ -64,6 +83,37 @@ fn deduct_load_from_cache(label: &str) -> Option<LabelOptions> {
         })
 }
 
+fn deduct_release(label: &str) -> Option<LabelOptions> {
+    match label.split('|').collect::<Vec<&str>>()[..] {
+        ["Release ", version] => Some(SelectRelease(version.to_string())),
+        _ => None,
+    }
+}
+
+fn deduct_pin(label: &str) -> Option<LabelOptions> {
+    match label.split('|').collect::<Vec<&str>>()[..] {
+        ["Pin ", short_name, " to ", version] => {
+            Some(PinVersion(short_name.to_string(), version.to_string()))
+        }
+        ["Unpin ", short_name, ..] => Some(Unpin(short_name.to_string())),
+        _ => None,
+    }
+}
+
+fn deduct_uninstall(label: &str) -> Option<LabelOptions> {
+    match label.split('|').collect::<Vec<&str>>()[..] {
+        ["Uninstall mod from ", short_name, ..] => Some(Uninstall(short_name.to_string())),
+        _ => None,
+    }
+}
+
+fn deduct_self_update(label: &str) -> Option<LabelOptions> {
+    match label.split('|').collect::<Vec<&str>>()[..] {
+        ["Update REFresher to ", version] => Some(SelfUpdate(version.to_string())),
+        _ => None,
+    }
+}
+
 fn deduct_switch_to(label: &str) -> Option<LabelOptions> {
     label
         .contains("Switch type to |")
@@ -102,6 +152,16 @@ impl LabelOptions {
                 "Load mod from cache |{}|{}|{}",
                 short_name, asset_name, version
             ),
+            InstallDifferentRelease => "Install different release...".to_string(),
+            MoreReleases => "More releases...".to_string(),
+            SelectRelease(version) => format!("Release |{}", version),
+            PinSection => "Pin version...".to_string(),
+            PinVersion(short_name, version) => format!("Pin |{}| to |{}", short_name, version),
+            Unpin(short_name) => format!("Unpin |{}|", short_name),
+            UninstallSection => "Uninstall mod...".to_string(),
+            Uninstall(short_name) => format!("Uninstall mod from |{}|", short_name),
+            RegisterGameSection => "Add game manually...".to_string(),
+            SelfUpdate(version) => format!("Update REFresher to |{}", version),
             Other => "Other".to_string(),
             Back => "Back".to_string(),
             GoTop => "Back to download section".to_string(),
@@ -1,7 +1,6 @@
-use args::{parse_args, ArgsClap};
+use args::{get_command, parse_args, ArgsClap};
 use dialogs::dialogs::Dialogs;
 use log::info;
-#[cfg(target_os = "windows")]
 use rManager::rManager_header::REvilManager;
 use reframework_github::refr_github::{self, REFRGithub};
 
@@ -16,20 +15,32 @@ use tomlConf::config::REvilConfigProvider;
 use utils::local_version::LocalProvider;
 
 pub mod reframework_github {
+    pub mod local_folder;
     pub mod refr_github;
     pub mod release;
+    pub mod release_source;
 }
 mod utils {
     pub mod binSearch;
     pub mod fetch;
     pub mod find_game_conf_by_steam_id;
+    pub mod game_registry;
     pub mod get_local_path_to_cache;
     pub mod init_logger;
+    pub mod install_manifest;
     pub mod is_asset_tdb;
+    pub mod launch_args;
     pub mod local_version;
     pub mod open_dialog;
     pub mod progress_style;
+    pub mod protected_paths;
+    pub mod release_selector;
     pub mod restart_program;
+    pub mod self_updater;
+    pub mod shortcut;
+    pub mod status_report;
+    pub mod steam_library;
+    pub mod timestamp;
     pub mod version_parser;
 }
 
@@ -44,6 +55,7 @@ pub mod unzip;
 mod tests {
     pub mod config_provider_mock;
     pub mod dialog_provider_mock;
+    pub mod http_stand_in;
     pub mod init_dialogs_mock;
     pub mod integration;
     pub mod local_provider_mock;
@@ -67,22 +79,15 @@ mod tomlConf {
     pub mod configStruct;
     #[cfg(test)]
     pub mod configTest;
+    pub mod document;
     pub mod utils;
 }
 
 pub type DynResult<T> = Result<T, Box<dyn Error>>;
 
 static NIGHTLY_RELEASE: &str = "REFramework-nightly";
+static STABLE_RELEASE: &str = "REFramework";
 static REPO_OWNER: &str = "praydog";
-static GAMES: [(&str, &str); 6] = [
-    ("601150", "DMC5"),
-    ("1446780", "MHRISE"),
-    ("883710", "RE2"),
-    ("952060", "RE3"),
-    ("418370", "RE7"),
-    ("1196590", "RE8"),
-];
-static GAMES_NEXTGEN_SUPPORT: [&str; 3] = ["RE2", "RE3", "RE7"];
 
 static mut ARGS: Option<ArgsClap> = None;
 
@@ -90,6 +95,15 @@ static STANDARD_TYPE_QUALIFIER: &str = "_TDB";
 
 static MAX_ZIP_FILES_PER_GAME_CACHE: u8 = 4;
 
+static MAX_DOWNLOAD_ATTEMPTS: u8 = 2;
+
+// partial downloads not resumed for this long are removed at startup
+static PARTIAL_DOWNLOAD_MAX_AGE_DAYS: u64 = 7;
+
+static MAX_PARALLEL_DOWNLOADS: u8 = 3;
+
+static MAX_CONFIG_BACKUPS: u8 = 3;
+
 static TIME_TO_CLOSE: u16 = 10;
 
 fn main() -> Result<(), Box<dyn error::Error>> {
@@ -97,7 +111,7 @@ fn main() -> Result<(), Box<dyn error::Error>> {
         parse_args();
     };
     let config_provider = Box::new(REvilConfigProvider::new("config.toml"));
-    let steam_menago = Box::new(SteamManager);
+    let steam_menago = Box::new(SteamManager::default());
     let local_provider = Box::new(LocalProvider);
     let dialogs = Box::new(Dialogs);
     let mut evil_manager = REvilManager::new(
@@ -110,6 +124,10 @@ fn main() -> Result<(), Box<dyn error::Error>> {
 
     let strategy = StrategyFactory::get_strategy(&mut evil_manager);
     strategy(&mut evil_manager);
+    // commands are meant for scripts so there is no window to keep open
+    if get_command().is_some() {
+        return Ok(());
+    }
     info!("This window will close after {} seconds", TIME_TO_CLOSE);
     let secs = time::Duration::from_secs(TIME_TO_CLOSE as u64);
     thread::sleep(secs);
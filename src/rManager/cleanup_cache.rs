@@ -1,43 +1,444 @@
-use std::fs;
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fmt, fs,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
 
 use std::path::Path;
 
+use crate::rManager::rManager::get_pinned_version;
 use crate::rManager::rManager_header::REvilManagerError;
-use crate::utils::get_local_path_to_cache::get_local_path_to_cache_folder;
+use crate::reframework_github::release_source::get_game_channel;
+use crate::tomlConf::configStruct::{GameConfig, Main, ShortGameName, VersionRecord};
+use crate::utils::get_local_path_to_cache::{
+    get_local_path_to_cache_folder, CHANNELS_CACHE_FOLDER, PARTIAL_DOWNLOAD_EXTENSION,
+};
+use crate::utils::timestamp::parse_rfc3339;
+use crate::{DynResult, MAX_ZIP_FILES_PER_GAME_CACHE};
 
-use error_stack::{IntoReport, Report, ResultExt};
+use error_stack::{IntoReport, ResultExt};
 
 use log::debug;
 
 use crate::rManager::rManager_header::ResultManagerErr;
 
-pub fn cleanup_cache(last_ver: &Vec<String>, game_short_name: &str) -> ResultManagerErr<()> {
-    if last_ver.len() < 2 {
+const SECONDS_PER_DAY: u64 = 86_400;
+const BYTES_PER_MB: u64 = 1024 * 1024;
+
+// limits of mod cache from [main] section, versions over any of them are removed in one pass
+pub struct CachePolicy {
+    pub max_versions_per_game: usize,
+    pub max_total_bytes: Option<u64>,
+    pub max_age: Option<Duration>,
+}
+
+impl CachePolicy {
+    pub fn from_main(main: &Main) -> Self {
+        CachePolicy {
+            max_versions_per_game: main
+                .max_cache_versions_per_game
+                .unwrap_or(MAX_ZIP_FILES_PER_GAME_CACHE)
+                .into(),
+            max_total_bytes: main.max_cache_size_mb.map(|mb| mb * BYTES_PER_MB),
+            max_age: main
+                .max_cache_age_days
+                .map(|days| Duration::from_secs(u64::from(days) * SECONDS_PER_DAY)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EvictionReason {
+    Age,
+    Count,
+    Size,
+}
+
+impl fmt::Display for EvictionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EvictionReason::Age => write!(f, "older than max_cache_age_days"),
+            EvictionReason::Count => write!(f, "over max_cache_versions_per_game"),
+            EvictionReason::Size => write!(f, "over max_cache_size_mb"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CacheEviction {
+    pub game_short_name: ShortGameName,
+    pub version: String,
+    pub files: Vec<PathBuf>,
+    pub bytes: u64,
+    pub reason: EvictionReason,
+}
+
+struct CachedVersion<'a> {
+    game_short_name: &'a str,
+    record: &'a VersionRecord,
+    files: Vec<PathBuf>,
+    bytes: u64,
+    downloaded: Option<SystemTime>,
+    protected: bool,
+    evicted: Option<EvictionReason>,
+}
+
+pub fn format_size(bytes: u64) -> String {
+    format!("{:.1} MB", bytes as f64 / BYTES_PER_MB as f64)
+}
+
+// version in use, pinned one and ones with keep = true are never removed
+fn is_protected(game_config: &GameConfig, record: &VersionRecord) -> bool {
+    record.keep.unwrap_or_default()
+        || game_config.version_in_use.as_ref() == Some(&record.version)
+        || get_pinned_version(game_config).as_ref() == Some(&record.version)
+}
+
+fn get_cached_versions<'a>(
+    games: &'a HashMap<ShortGameName, GameConfig>,
+) -> Vec<CachedVersion<'a>> {
+    let mut game_names: Vec<_> = games.keys().collect();
+    game_names.sort();
+    game_names
+        .into_iter()
+        .flat_map(|game_short_name| {
+            let game_config = &games[game_short_name];
+            game_config.versions.iter().flatten().map(move |record| {
+                let cache_dir = get_local_path_to_cache_folder(
+                    None,
+                    Some(&record.version),
+                    get_game_channel(game_config),
+                )
+                .ok();
+                let files: Vec<(PathBuf, fs::Metadata)> = record
+                    .assets
+                    .iter()
+                    .filter_map(|asset| {
+                        let file = cache_dir.as_ref()?.join(&asset.name);
+                        let metadata = fs::metadata(&file).ok()?;
+                        Some((file, metadata))
+                    })
+                    .collect();
+                // migrated records have no download time so archive modification time is used
+                let downloaded = record
+                    .downloaded
+                    .as_deref()
+                    .and_then(parse_rfc3339)
+                    .or_else(|| {
+                        files
+                            .iter()
+                            .filter_map(|(_, metadata)| metadata.modified().ok())
+                            .max()
+                    });
+                CachedVersion {
+                    game_short_name,
+                    record,
+                    bytes: files.iter().map(|(_, metadata)| metadata.len()).sum(),
+                    files: files.into_iter().map(|(file, _)| file).collect(),
+                    downloaded,
+                    protected: is_protected(game_config, record),
+                    evicted: None,
+                }
+            })
+        })
+        .collect()
+}
+
+// versions of each game are ordered from the newest so the oldest ones are removed first
+pub fn find_cache_evictions(
+    games: &HashMap<ShortGameName, GameConfig>,
+    policy: &CachePolicy,
+    now: SystemTime,
+) -> Vec<CacheEviction> {
+    let mut cached = get_cached_versions(games);
+
+    if let Some(max_age) = policy.max_age {
+        cached
+            .iter_mut()
+            .filter(|version| !version.protected)
+            .filter(|version| {
+                version
+                    .downloaded
+                    .and_then(|downloaded| now.duration_since(downloaded).ok())
+                    .map(|age| age > max_age)
+                    .unwrap_or_default()
+            })
+            .for_each(|version| version.evicted = Some(EvictionReason::Age));
+    }
+
+    let mut game_names: Vec<_> = games.keys().collect();
+    game_names.sort();
+    for game_short_name in game_names {
+        let kept = cached
+            .iter()
+            .filter(|version| {
+                version.game_short_name == game_short_name && version.evicted.is_none()
+            })
+            .count();
+        cached
+            .iter_mut()
+            .rev()
+            .filter(|version| {
+                version.game_short_name == game_short_name
+                    && version.evicted.is_none()
+                    && !version.protected
+            })
+            .take(kept.saturating_sub(policy.max_versions_per_game))
+            .for_each(|version| version.evicted = Some(EvictionReason::Count));
+    }
+
+    if let Some(max_total_bytes) = policy.max_total_bytes {
+        let mut total_bytes: u64 = cached
+            .iter()
+            .filter(|version| version.evicted.is_none())
+            .map(|version| version.bytes)
+            .sum();
+        // the oldest download goes first, versions without download time are treated as the oldest
+        let mut candidates: Vec<_> = cached
+            .iter_mut()
+            .filter(|version| version.evicted.is_none() && !version.protected && version.bytes > 0)
+            .collect();
+        candidates.sort_by_key(|version| version.downloaded);
+        for version in candidates {
+            if total_bytes <= max_total_bytes {
+                break;
+            }
+            total_bytes -= version.bytes;
+            version.evicted = Some(EvictionReason::Size);
+        }
+    }
+
+    cached
+        .into_iter()
+        .filter_map(|version| {
+            Some(CacheEviction {
+                reason: version.evicted?,
+                game_short_name: version.game_short_name.to_string(),
+                version: version.record.version.to_string(),
+                files: version.files,
+                bytes: version.bytes,
+            })
+        })
+        .collect()
+}
+
+// removes cached archives of evicted versions and their records from game configs
+pub fn evict_cache(
+    games: &mut HashMap<ShortGameName, GameConfig>,
+    evictions: &[CacheEviction],
+) -> ResultManagerErr<()> {
+    for eviction in evictions {
+        for file in eviction.files.iter() {
+            if file.exists() {
+                fs::remove_file(file).report().change_context(
+                    REvilManagerError::RemoveZipAssetFromCacheErr(file.display().to_string()),
+                )?;
+            }
+            if let Some(cache_dir) = file.parent() {
+                remove_empty_cache_dir(cache_dir);
+            }
+        }
+        if let Some(game_config) = games.get_mut(&eviction.game_short_name) {
+            if let Some(versions) = game_config.versions.as_mut() {
+                versions.retain(|record| record.version != eviction.version);
+            }
+            // game without cached versions is treated as one without mod installed
+            if game_config.versions.as_ref().is_some_and(Vec::is_empty) {
+                game_config.versions = None;
+            }
+        }
         debug!(
-            "A Game {} Cache warn: {:?}",
-            game_short_name,
-            REvilManagerError::CacheNotFoundForGivenVersion(last_ver[0].to_string()).to_string()
+            "Removed {} {} from cache ({})",
+            eviction.game_short_name, eviction.version, eviction.reason
         );
-        return Ok(());
-    }
-    let last_ver_nb = &last_ver[0];
-    let cache_dir = get_local_path_to_cache_folder(None, Some(last_ver_nb))
-        .map_err(|_| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
-    if cache_dir.exists() {
-        let file_to_remove = cache_dir.join(&last_ver[1]);
-        if Path::new(&file_to_remove).exists() {
-            fs::remove_file(&file_to_remove).report().change_context(
-                REvilManagerError::RemoveZipAssetFromCacheErr(file_to_remove.display().to_string()),
-            )?;
-        }
-        match fs::remove_dir(&cache_dir) {
-            Ok(()) => debug!("Directory: {} Removed", cache_dir.display().to_string()),
-            Err(err) => debug!(
-                "Can not Remove directory: {} Err {}",
-                cache_dir.display().to_string(),
-                err
-            ),
-        };
-    };
+    }
     Ok(())
 }
+
+// version folder is shared by games so it is removed only when nothing is left in it
+fn remove_empty_cache_dir(cache_dir: &Path) {
+    match fs::remove_dir(cache_dir) {
+        Ok(()) => debug!("Directory: {} Removed", cache_dir.display().to_string()),
+        Err(err) => debug!(
+            "Can not Remove directory: {} Err {}",
+            cache_dir.display().to_string(),
+            err
+        ),
+    };
+}
+
+// removes leftovers of interrupted downloads older than max_age from version folders of the cache.
+// Newer ones are kept so the next download resumes them. Returns number of removed files
+pub fn remove_partial_downloads(
+    cache_folder: &Path,
+    max_age: Duration,
+    now: SystemTime,
+) -> DynResult<usize> {
+    if !cache_folder.exists() {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for version_dir in fs::read_dir(cache_folder)? {
+        let version_dir = version_dir?.path();
+        if !version_dir.is_dir() {
+            continue;
+        }
+        // each channel folder has version folders of its own
+        if version_dir.file_name() == Some(OsStr::new(CHANNELS_CACHE_FOLDER)) {
+            for channel_dir in fs::read_dir(&version_dir)? {
+                let channel_dir = channel_dir?.path();
+                if channel_dir.is_dir() {
+                    removed += remove_partial_downloads(&channel_dir, max_age, now)?;
+                }
+            }
+            continue;
+        }
+        let mut removed_from_dir = 0;
+        for file in fs::read_dir(&version_dir)? {
+            let file = file?.path();
+            if !file.is_file() || file.extension() != Some(OsStr::new(PARTIAL_DOWNLOAD_EXTENSION)) {
+                continue;
+            }
+            let is_stale = fs::metadata(&file)
+                .and_then(|metadata| metadata.modified())
+                .map(|modified| {
+                    now.duration_since(modified)
+                        .map(|age| age > max_age)
+                        .unwrap_or_default()
+                })
+                .unwrap_or(true);
+            if is_stale {
+                fs::remove_file(&file)?;
+                debug!("Removed partial download {}", file.display());
+                removed_from_dir += 1;
+            }
+        }
+        // folder of a version which download never completed is left empty
+        if removed_from_dir > 0 && fs::remove_dir(&version_dir).is_ok() {
+            debug!("Removed empty cache folder {}", version_dir.display());
+        }
+        removed += removed_from_dir;
+    }
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_partial_downloads_keeps_complete_assets_and_recent_parts() {
+        let cache_folder = std::env::temp_dir().join("refresher_partial_downloads");
+        if cache_folder.exists() {
+            fs::remove_dir_all(&cache_folder).unwrap();
+        }
+        fs::create_dir_all(cache_folder.join("v1.71-abd3145")).unwrap();
+        fs::create_dir_all(cache_folder.join("v1.72-bbd3145")).unwrap();
+        fs::write(cache_folder.join("v1.71-abd3145/RE2.zip"), "x").unwrap();
+        fs::write(cache_folder.join("v1.71-abd3145/RE8.zip.part"), "x").unwrap();
+        fs::write(cache_folder.join("v1.72-bbd3145/RE2.zip.part"), "x").unwrap();
+        fs::create_dir_all(cache_folder.join("channels/stable/v1.5.9")).unwrap();
+        fs::write(
+            cache_folder.join("channels/stable/v1.5.9/RE4.zip.part"),
+            "x",
+        )
+        .unwrap();
+
+        let max_age = Duration::from_secs(7 * SECONDS_PER_DAY);
+        assert_eq!(
+            remove_partial_downloads(&cache_folder, max_age, SystemTime::now()).unwrap(),
+            0
+        );
+        assert!(cache_folder.join("v1.71-abd3145/RE8.zip.part").exists());
+
+        let week_later = SystemTime::now() + Duration::from_secs(8 * SECONDS_PER_DAY);
+        assert_eq!(
+            remove_partial_downloads(&cache_folder, max_age, week_later).unwrap(),
+            3
+        );
+        assert!(!cache_folder.join("channels/stable/v1.5.9").exists());
+        assert!(cache_folder.join("v1.71-abd3145/RE2.zip").exists());
+        assert!(!cache_folder.join("v1.71-abd3145/RE8.zip.part").exists());
+        assert!(!cache_folder.join("v1.72-bbd3145").exists());
+        fs::remove_dir_all(&cache_folder).unwrap();
+    }
+
+    fn downloaded_record(version: &str, downloaded: &str) -> VersionRecord {
+        VersionRecord {
+            downloaded: Some(downloaded.to_string()),
+            ..VersionRecord::new(version)
+        }
+    }
+
+    #[test]
+    fn cache_policy_keeps_protected_versions() {
+        let mut kept = downloaded_record("v1.0", "2026-01-01T00:00:00Z");
+        kept.keep = Some(true);
+        let game_config = GameConfig {
+            version_in_use: Some("v1.1".to_string()),
+            versions: Some(vec![
+                downloaded_record("v1.4", "2026-10-10T00:00:00Z"),
+                downloaded_record("v1.3", "2026-10-01T00:00:00Z"),
+                downloaded_record("v1.2", "2026-09-01T00:00:00Z"),
+                downloaded_record("v1.1", "2026-01-01T00:00:00Z"),
+                kept,
+            ]),
+            ..GameConfig::default()
+        };
+        let games = HashMap::from([("RE2".to_string(), game_config)]);
+        let now = parse_rfc3339("2026-10-17T00:00:00Z").unwrap();
+
+        let count_policy = CachePolicy {
+            max_versions_per_game: 3,
+            max_total_bytes: None,
+            max_age: None,
+        };
+        let evictions = find_cache_evictions(&games, &count_policy, now);
+        assert_eq!(
+            evictions
+                .iter()
+                .map(|eviction| (eviction.version.as_str(), eviction.reason))
+                .collect::<Vec<_>>(),
+            vec![
+                ("v1.3", EvictionReason::Count),
+                ("v1.2", EvictionReason::Count)
+            ]
+        );
+
+        let age_policy = CachePolicy {
+            max_versions_per_game: 10,
+            max_total_bytes: None,
+            max_age: Some(Duration::from_secs(30 * SECONDS_PER_DAY)),
+        };
+        let evictions = find_cache_evictions(&games, &age_policy, now);
+        assert_eq!(
+            evictions
+                .iter()
+                .map(|eviction| (eviction.version.as_str(), eviction.reason))
+                .collect::<Vec<_>>(),
+            vec![("v1.2", EvictionReason::Age)]
+        );
+    }
+
+    #[test]
+    fn game_without_cached_versions_has_no_versions() {
+        let mut games = HashMap::from([(
+            "RE2".to_string(),
+            GameConfig {
+                versions: Some(vec![VersionRecord::new("v1.0")]),
+                ..GameConfig::default()
+            },
+        )]);
+        let eviction = CacheEviction {
+            game_short_name: "RE2".to_string(),
+            version: "v1.0".to_string(),
+            files: Vec::new(),
+            bytes: 0,
+            reason: EvictionReason::Count,
+        };
+        evict_cache(&mut games, &[eviction]).unwrap();
+        assert_eq!(games["RE2"].versions, None);
+    }
+}
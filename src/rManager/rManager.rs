@@ -1,54 +1,93 @@
-use std::{cmp::Ordering, collections::HashMap, env, ffi::OsStr, fs};
+use std::{
+    cmp::Ordering,
+    collections::{BTreeSet, HashMap},
+    env,
+    ffi::OsStr,
+    fs,
+    sync::atomic::{self, AtomicUsize},
+    thread,
+};
 
 use crate::{
-    args::RunAfter,
+    args::{get_command, Command, RunAfter, StatusFormat},
     dialogs::{
-        dialogs::{Ask, DialogsErrors, SwitchActionReport},
+        dialogs::{
+            get_switch_type_action, set_runtime_and_find_cached_version, Ask, DialogsErrors,
+            SwitchActionReport,
+        },
         dialogs_label::LabelOptions,
     },
-    rManager::cleanup_cache::cleanup_cache,
+    rManager::cleanup_cache::{evict_cache, find_cache_evictions, format_size, CachePolicy},
     rManager::rManager_header::{
         REvilManager, REvilManagerError, REvilManagerState, REvilThings, ResultManagerErr,
         SORT_DETERMINER,
     },
     refr_github::REFRGithub,
-    reframework_github::refr_github::ManageGithub,
+    reframework_github::{
+        local_folder::LocalFolderRelease,
+        refr_github::{AssetsReport, GithubOptions, ManageGithub, REFRGithubError},
+        release_source::{get_game_channel, ReleaseSource},
+    },
     steam::SteamThings,
     tomlConf::{
         config::ConfigProvider,
-        configStruct::{ErrorLevel, GameConfig, Main, REvilConfig, Runtime, ShortGameName},
+        configStruct::{
+            ConfigError, Edition, ErrorLevel, GameConfig, Main, PendingAction, REvilConfig,
+            Runtime, ShortGameName, SteamId, VersionRecord,
+        },
     },
     utils::{
+        fetch::GITHUB_TOKEN_ENV,
         find_game_conf_by_steam_id::find_game_conf_by_steam_id,
+        game_registry::{
+            game_supports_both_editions, get_short_name_from_asset_name, set_game_registry,
+            with_game_registry, GameEntry, GameRegistry,
+        },
         get_local_path_to_cache::get_local_path_to_cache_folder,
         init_logger::init_logger,
+        install_manifest::{
+            get_manifest_path, load_manifest, remove_installed_files, remove_manifest,
+            to_relative_paths, update_manifest,
+        },
         is_asset_tdb::is_asset_tdb,
+        launch_args::combine_launch_args,
         local_version::LocalFiles,
         progress_style,
-        restart_program::restart_program,
+        protected_paths::ProtectedPaths,
+        release_selector::{find_release, ReleaseSelector, RELEASES_PER_PAGE},
+        restart_program::{restart_program, restart_with_same_args},
+        self_updater::{
+            find_executable_asset, find_newer_release, install_executable, rollback_executable,
+            SELF_REPO_NAME, SELF_REPO_OWNER, SELF_UPDATE_CHANNEL, SELF_UPDATE_RELEASES_TO_CHECK,
+        },
+        shortcut::{
+            get_applications_folder, get_shortcut_file_name, read_shortcuts_target,
+            save_shortcuts_target,
+        },
+        status_report::StatusReport,
+        timestamp::now_rfc3339,
         version_parser::{isRepoVersionNewer, HASH_DELIMITER},
     },
-    DynResult, ARGS, GAMES, MAX_ZIP_FILES_PER_GAME_CACHE, NIGHTLY_RELEASE, REPO_OWNER,
-    STANDARD_TYPE_QUALIFIER,
+    ARGS, MAX_DOWNLOAD_ATTEMPTS, MAX_PARALLEL_DOWNLOADS, MAX_ZIP_FILES_PER_GAME_CACHE,
 };
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[cfg(test)]
 use crate::unzip::unzip::mock_unzip as unzip;
 #[cfg(not(test))]
 use crate::unzip::unzip::unzip;
+use crate::unzip::unzip::{list_archive_files, UnzipError};
 
 use error_stack::{IntoReport, Report, Result, ResultExt};
 use log::{debug, error, info, log, trace, warn, Level};
-use self_update::update::ReleaseAsset;
-use std::time::Duration;
+use self_update::update::{Release, ReleaseAsset};
+use std::time::{Duration, SystemTime};
 
-use indicatif::ProgressBar;
+use indicatif::{MultiProgress, ProgressBar};
 
 use super::rManager_header::AfterUnzipOption;
 
-pub static SWITCH_IDENTIFIER: &str = "switch";
-pub static UPDATE_IDENTIFIER: &str = "update_me";
+pub static PIN_CURRENT: &str = "current";
 
 impl REvilManager {
     pub fn new(
@@ -71,6 +110,8 @@ impl REvilManager {
             local_provider,
             refr_ctor: github_constr,
             github_release_manager: None,
+            channel_release_managers: HashMap::new(),
+            self_release_manager: None,
             state: REvilManagerState::default(),
             dialogs,
         }
@@ -91,6 +132,13 @@ impl REvilThings for REvilManager {
             })?;
         self.config = config;
         self.attach_logger()?;
+        self.config.invalid_sections.iter().for_each(|section| {
+            warn!(
+                "{}. Section is left in config file unchanged, fix it or remove it",
+                section.error
+            )
+        });
+        set_game_registry(GameRegistry::from_config(&self.config.games));
         info!("config loaded successfully, logger initialized");
         Ok(self)
     }
@@ -108,24 +156,39 @@ impl REvilThings for REvilManager {
                 .as_ref()
                 .unwrap_or(&ErrorLevel::info);
         }
-        println!("Level {}", level);
+        // machine readable status goes to stdout so logs have to go elsewhere
+        let machine_output = matches!(
+            get_command(),
+            Some(Command::Status { format }) if format != StatusFormat::text
+        );
+        if machine_output {
+            eprintln!("Level {}", level);
+        } else {
+            println!("Level {}", level);
+        }
 
-        init_logger(level.to_string().as_ref());
+        init_logger(level.to_string().as_ref(), machine_output);
 
         Ok(self)
     }
 
     fn load_games_from_steam(&mut self) -> ResultManagerErr<&mut Self> {
         info!("Going to auto-detect games");
-        let game_ids = GAMES.map(|(k, _v)| k);
+        self.steam_menago
+            .set_steam_path(self.config.main.steamExePath.as_ref().map(PathBuf::from));
+        let games: Vec<(SteamId, ShortGameName)> =
+            with_game_registry(|registry| get_games_to_search_for(registry, &self.config.main));
+        let game_ids: Vec<SteamId> = games.iter().map(|(id, _)| id.to_string()).collect();
         let games_tuple_arr = self
             .steam_menago
-            .get_games_locations(&game_ids.to_vec())
+            .get_games_locations(&game_ids)
             .change_context(REvilManagerError::default())?;
 
         games_tuple_arr.iter().for_each(|(id, path)| {
-            // unwrap call here is ok as we don't expect different game as GAMES where passed to get_games_locations earlier too
-            let (_, game_short_name) = GAMES.iter().find(|(game_id, _)| game_id == id).unwrap();
+            let game_short_name = match games.iter().find(|(game_id, _)| game_id == id) {
+                Some((_, short_name)) => short_name,
+                None => return,
+            };
 
             info!("game detected name {}, path {:?}", game_short_name, path);
 
@@ -141,6 +204,13 @@ impl REvilThings for REvilManager {
                 .games
                 .entry(game_short_name.to_string())
                 .and_modify(|game| {
+                    if game.manual.unwrap_or_default() {
+                        debug!(
+                            "{} location is registered manually, Steam location skipped",
+                            game_short_name
+                        );
+                        return;
+                    }
                     let runtime = game
                         .runtime
                         .clone()
@@ -151,6 +221,12 @@ impl REvilThings for REvilManager {
                         runtime: Some(runtime),
                         nextgen: game.nextgen,
                         runArgs: game.runArgs.clone(),
+                        channel: game.channel.clone(),
+                        pin: game.pin.clone(),
+                        protected_paths: game.protected_paths.clone(),
+                        asset_pattern: game.asset_pattern.clone(),
+                        editions: game.editions.clone(),
+                        executable: game.executable.clone(),
                         versions: game.versions.clone(),
                         version_in_use: game.version_in_use.clone(),
                         ..game_config.clone()
@@ -180,7 +256,10 @@ impl REvilThings for REvilManager {
         pb.enable_steady_tick(Duration::from_millis(80).as_secs());
         pb.set_style(progress_style::getProgressStyle());
         for (short_name, config) in self.config.games.iter_mut() {
-            let game_location = config.location.as_ref().unwrap();
+            // sections only overriding the game registry have no installed game to scan
+            let Some(game_location) = config.location.as_ref() else {
+                continue;
+            };
             pb.set_message(format!("Loading config from {} ...", game_location));
             pb.tick();
             let local_config = self
@@ -193,7 +272,7 @@ impl REvilThings for REvilManager {
             // replace versions information for other games
             if local_config.version.is_some() && config.versions.is_none() {
                 let local_ver = local_config.version.unwrap();
-                config.versions = Some([[local_ver.to_string()].to_vec()].to_vec());
+                config.versions = Some(vec![VersionRecord::new(&local_ver)]);
                 config.version_in_use = Some(local_ver);
             }
             config.nextgen = local_config.nextgen;
@@ -204,79 +283,150 @@ impl REvilThings for REvilManager {
         self
     }
 
-    fn generate_ms_links(&mut self) -> ResultManagerErr<&mut Self> {
-        let current_exe_path =
-            env::current_exe()
-                .report()
-                .change_context(REvilManagerError::FailedToCreateMsLink(
-                    "Env::current_exe fail".to_string(),
-                ))?;
+    // shortcuts are regenerated when REFresher executable has been moved since they were created
+    fn generate_shortcuts(&mut self) -> ResultManagerErr<&mut Self> {
+        let current_exe_path = env::current_exe().report().change_context(
+            REvilManagerError::FailedToCreateShortcut("Env::current_exe fail".to_string()),
+        )?;
 
-        let ms_links_folder = self.local_provider.create_cache_dir()?;
+        let shortcuts_folder = self.local_provider.create_cache_dir()?;
+        let target_changed =
+            read_shortcuts_target(&shortcuts_folder).as_ref() != Some(&current_exe_path);
+        let mut folders = vec![shortcuts_folder.to_path_buf()];
+        if self
+            .config
+            .main
+            .shortcuts_in_applications_menu
+            .unwrap_or_default()
+        {
+            match get_applications_folder() {
+                Some(folder) if fs::create_dir_all(&folder).is_ok() => folders.push(folder),
+                _ => warn!("Applications menu folder not found, shortcuts are only in REFR_links"),
+            }
+        }
 
         self.config
             .games
             .iter()
-            .try_for_each(|(short_name, _)| -> ResultManagerErr<()> {
-                let ms_link_name = format!("REFR_{}.lnk", short_name);
-                let ms_link_path = ms_links_folder.join(Path::new(&ms_link_name));
-                if ms_link_path.exists() {
-                    debug!(
-                        "Ms link already exists for {} Path {}",
-                        short_name,
-                        ms_link_path.display()
-                    );
-                    return Ok(());
-                }
+            .filter(|(_, game_config)| game_config.location.is_some())
+            .for_each(|(short_name, _)| {
+                folders.iter().for_each(|folder| {
+                    let shortcut_path = folder.join(get_shortcut_file_name(short_name));
+                    if shortcut_path.exists() && !target_changed {
+                        debug!(
+                            "Shortcut already exists for {} Path {}",
+                            short_name,
+                            shortcut_path.display()
+                        );
+                        return;
+                    }
 
-                let arguments = format!("--one {}", short_name);
-                self.local_provider
-                    .create_ms_lnk(&ms_link_path, &current_exe_path, Some(arguments.clone()))
-                    .or_else(|err| {
-                        Err(Report::new(REvilManagerError::FailedToCreateMsLink(
-                            format!(
-                                "Failed for {} Ms Link path {} Current exe path {} args {}",
+                    let arguments = format!("--one {}", short_name);
+                    self.local_provider
+                        .create_shortcut(&shortcut_path, &current_exe_path, Some(arguments.clone()))
+                        .or_else(|err| {
+                            Err(Report::new(REvilManagerError::FailedToCreateShortcut(
+                                format!(
+                                    "Failed for {} Shortcut path {} Current exe path {} args {}",
+                                    short_name,
+                                    shortcut_path.display(),
+                                    current_exe_path.display(),
+                                    arguments
+                                ),
+                            )))
+                            .attach_printable(format!("{:?}", err))
+                        })
+                        .map(|_| {
+                            info!(
+                                "Shortcut created for {} in {}",
                                 short_name,
-                                ms_link_path.display(),
-                                current_exe_path.display(),
-                                arguments
-                            ),
-                        )))
-                        .attach_printable(format!("{:?}", err))
-                    })
-                    .map(|_| info!("Ms link created for {}", short_name))
-                    .unwrap_or_else(|err| {
-                        warn!("{}", err);
-                        debug!("{:?}", err);
-                    });
-                Ok(())
-            })?;
+                                folder.display()
+                            )
+                        })
+                        .unwrap_or_else(|err| {
+                            warn!("{}", err);
+                            debug!("{:?}", err);
+                        });
+                });
+            });
+        if target_changed {
+            save_shortcuts_target(&shortcuts_folder, &current_exe_path).unwrap_or_else(|err| {
+                warn!("Couldn't save shortcuts target Err {}", err);
+            });
+        }
 
         Ok(self)
     }
 
     fn check_for_REFramework_update(&mut self) -> ResultManagerErr<&mut Self> {
         let main = &self.config.main;
-        let repo_owner = main
-            .repo_owner
-            .as_ref()
-            .map_or(REPO_OWNER.to_string(), |it| it.to_string());
-
-        let source: String = match &main.repo_name {
-            Some(it) => it.to_string(),
-            None => NIGHTLY_RELEASE.to_string(),
-        };
-        self.github_release_manager = Some((self.refr_ctor)(&repo_owner, &source));
+        let token = get_github_token(main);
 
         info!("Checking if new release exists");
-        let manager = self
-            .github_release_manager
-            .as_mut()
-            .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
-        manager.get_reframework_latest_release().or_else(|err| {
-            Err(Report::new(REvilManagerError::CheckingNewReleaseErr))
-                .attach_printable(format!("{:?}", err))
-        })?;
+        // sources are tried in order, the first one with a release containing assets wins
+        let mut last_err = Report::new(REvilManagerError::ReleaseIsEmpty);
+        self.github_release_manager = None;
+        for source in ReleaseSource::from_config(main) {
+            let mut manager =
+                create_release_manager(self.refr_ctor, &source, main, token.clone(), None);
+            match manager.get_reframework_latest_release() {
+                Ok(()) if !manager.getAssetsReport().is_empty() => {
+                    info!("Release source {} is used", manager.source_name());
+                    self.github_release_manager = Some(manager);
+                    break;
+                }
+                Ok(()) => {
+                    warn!("Release from source {} has no assets", source);
+                    last_err = Report::new(REvilManagerError::ReleaseIsEmpty);
+                }
+                Err(err) => {
+                    warn!("Release source {} failed: {}", source, err);
+                    last_err = to_checking_release_err(err);
+                }
+            }
+        }
+        if self.github_release_manager.is_none() {
+            return Err(last_err);
+        }
+
+        // games with a channel get releases only from that channel, each distinct one is checked once
+        self.channel_release_managers.clear();
+        let channels: BTreeSet<String> = self
+            .config
+            .games
+            .values()
+            .filter_map(|game| get_game_channel(game).map(String::from))
+            .collect();
+        for channel in channels {
+            let source = match ReleaseSource::parse(&channel, main) {
+                Some(it) => it,
+                None => {
+                    warn!("Release channel {} is not supported", channel);
+                    continue;
+                }
+            };
+            let mut manager = create_release_manager(
+                self.refr_ctor,
+                &source,
+                main,
+                token.clone(),
+                Some(&channel),
+            );
+            match manager.get_reframework_latest_release() {
+                Ok(()) => {
+                    info!(
+                        "Latest release of {} channel: {}",
+                        channel,
+                        manager
+                            .getRelease()
+                            .map(|release| release.name.to_string())
+                            .unwrap_or_default()
+                    );
+                    self.channel_release_managers.insert(channel, manager);
+                }
+                Err(err) => warn!("Release channel {} failed: {}", channel, err),
+            }
+        }
 
         // requires github_release_manager to be initialized
         self.set_games_that_require_update()?;
@@ -302,6 +452,14 @@ impl REvilThings for REvilManager {
         if should_run_after.to_bool() {
             self.state.selected_game_to_launch = Some(steam_id.to_string());
         };
+        if let Some(pinned_version) = get_pinned_version(game_config) {
+            info!(
+                "{} is pinned to {} version. Update skipped",
+                game_short_name, pinned_version
+            );
+            self.install_pinned_version_if_needed(game_short_name, &pinned_version)?;
+            return Ok(self);
+        }
         if !self
             .state
             .games_that_require_update
@@ -310,27 +468,20 @@ impl REvilThings for REvilManager {
             info!("Update not required for {}", game_short_name);
             return Ok(self);
         }
-        set_game_from_report_as_selected_to_download(
-            self.github_release_manager.as_ref(),
-            self.state.selected_assets.as_mut(),
-            game_config,
-            game_short_name,
-        )?;
-
-        Ok(self)
+        self.select_games_to_update(&[game_short_name.to_string()], None)
     }
 
     fn ask_for_decision(&mut self) -> ResultManagerErr<&mut Self> {
-        let report = self
-            .github_release_manager
-            .as_ref()
-            .map(|men| men.getAssetsReport())
-            .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
+        let report = get_assets_report_for_games(
+            self.github_release_manager.as_deref(),
+            &self.channel_release_managers,
+            &self.config.games,
+        )?;
         self.dialogs
             .ask_for_decision_and_populate_selected_assets(
                 &mut self.config,
                 &mut self.state,
-                report,
+                &report,
             )
             .map_err(|err| match err.current_context() {
                 DialogsErrors::NoGamesToUpdate => REvilManagerError::NoGamesToUpdate,
@@ -340,28 +491,41 @@ impl REvilThings for REvilManager {
     }
 
     fn download_REFramework_update(&mut self) -> ResultManagerErr<&mut Self> {
-        let results: Vec<(String, ResultManagerErr<()>)> = self
+        if self.state.selected_assets.is_empty() {
+            return Ok(self);
+        }
+        let max_parallel = self
+            .config
+            .main
+            .max_parallel_downloads
+            .unwrap_or(MAX_PARALLEL_DOWNLOADS);
+        let downloads = self
             .state
             .selected_assets
             .iter()
-            .map(|asset| -> (String, ResultManagerErr<()>) {
-                let asset_name_result = self
-                    .github_release_manager
-                    .as_ref()
-                    .unwrap()
-                    .download_release_asset(asset)
-                    .map_err(|err| {
-                        Err(Report::new(REvilManagerError::DownloadAssetError(
-                            asset.name.clone(),
-                        )))
-                        .attach_printable(format!("{:?}", err))
-                    })
-                    .map(|_| (asset.name.to_string(), Ok(())))
-                    .unwrap_or_else(|err| (asset.name.to_string(), err));
-
-                asset_name_result
+            .map(|asset| -> ResultManagerErr<AssetDownload> {
+                let game_config = get_game_short_name_from_asset(asset)
+                    .ok()
+                    .and_then(|short_name| self.config.games.get(&short_name));
+                let manager = get_release_manager_for_game(
+                    self.github_release_manager.as_deref(),
+                    &self.channel_release_managers,
+                    game_config,
+                )
+                .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
+                info!(
+                    "Asset {} is supplied by {}",
+                    asset.name,
+                    manager.source_name()
+                );
+                Ok(AssetDownload {
+                    manager,
+                    asset,
+                    channel: game_config.and_then(get_game_channel),
+                })
             })
-            .collect();
+            .collect::<ResultManagerErr<Vec<AssetDownload>>>()?;
+        let results = download_assets_concurrently(&downloads, max_parallel);
 
         results.iter().for_each(|(asset_name, result)| {
             result.as_ref().unwrap_or_else(|err| {
@@ -399,27 +563,38 @@ impl REvilThings for REvilManager {
                 game_short_name.to_string(),
             ))
         })?;
-        let manager = self
-            .github_release_manager
-            .as_ref()
-            .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
-        let release = manager.getRelease();
-        let path_to_zip = get_local_path_to_cache_folder(release, version)
-            .map(|path| path.join(file_name))
-            .map_err(|_| Report::new(REvilManagerError::GetLocalPathToCacheErr))?;
+        // release is not needed when version is given, e.g. when switching type from cache
+        let release = get_release_manager_for_game(
+            self.github_release_manager.as_deref(),
+            &self.channel_release_managers,
+            Some(game_config),
+        )
+        .and_then(|manager| manager.getRelease());
+        let path_to_zip =
+            get_local_path_to_cache_folder(release, version, get_game_channel(game_config))
+                .map(|path| path.join(file_name))
+                .map_err(|_| Report::new(REvilManagerError::GetLocalPathToCacheErr))?;
         let location = game_config
             .location
             .as_ref()
             .ok_or_else(|| Report::new(REvilManagerError::GameLocationMissing))?;
+        let protected_paths = ProtectedPaths::from_config(&self.config.main, game_config);
 
         if unzip_skip_fun.is_some() {
-            unzip(&path_to_zip, &location, unzip_skip_fun).change_context(
-                REvilManagerError::UnzipError(format!(
+            let written_files = unzip(&path_to_zip, location, unzip_skip_fun, &protected_paths)
+                .map_err(|err| remove_corrupted_zip(err, &path_to_zip))
+                .change_context(REvilManagerError::UnzipError(format!(
                     "Couldn't unzip asset {}: for {} game.",
                     path_to_zip.display(),
                     game_short_name
-                )),
-            )?;
+                )))?;
+            record_installed_files(
+                game_short_name,
+                location,
+                &written_files,
+                false,
+                &protected_paths,
+            );
             return Ok(self);
         };
         let closure = game_config
@@ -435,13 +610,20 @@ impl REvilThings for REvilManager {
                 Box::new(should_skip)
             });
 
-        unzip(&path_to_zip, location, Some(closure)).change_context(
-            REvilManagerError::UnzipError(format!(
+        let written_files = unzip(&path_to_zip, location, Some(closure), &protected_paths)
+            .map_err(|err| remove_corrupted_zip(err, &path_to_zip))
+            .change_context(REvilManagerError::UnzipError(format!(
                 "Couldn't unzip asset {}: for {} game",
                 path_to_zip.display(),
                 game_short_name
-            )),
-        )?;
+            )))?;
+        record_installed_files(
+            game_short_name,
+            location,
+            &written_files,
+            self.config.main.clean_install.unwrap_or_default(),
+            &protected_paths,
+        );
         Ok(self)
     }
 
@@ -458,9 +640,8 @@ impl REvilThings for REvilManager {
                     }
                 };
 
-                let game_short_name = game_short_name;
                 let asset_name_result = self
-                    .unzip_update::<fn(&OsStr) -> bool>(game_short_name, &asset.name, None, None)
+                    .unzip_update::<fn(&OsStr) -> bool>(&game_short_name, &asset.name, None, None)
                     .map(|_| (asset.name.to_string(), Ok(())))
                     .unwrap_or_else(|err| (asset.name.to_string(), Err(err)));
 
@@ -494,21 +675,13 @@ impl REvilThings for REvilManager {
         &mut self,
         options: Option<Vec<AfterUnzipOption>>,
     ) -> Result<&mut Self, REvilManagerError> {
+        // only assets which install has been committed are left in selected_assets (failed ones are removed in unzip_updates)
+        // so version is never recorded for a game that was rolled back
         let selected_assets = &self.state.selected_assets;
-        let manager = self
-            .github_release_manager
-            .as_ref()
-            .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
-        let release = manager.getRelease();
-        let version: &str = release
-            .as_ref()
-            .ok_or_else(|| Report::new(REvilManagerError::ReleaseIsEmpty))?
-            .name
-            .as_ref();
         let results: Vec<ResultManagerErr<()>> = selected_assets
             .iter()
             .map(|asset| -> ResultManagerErr<()> {
-                let game_short_name = get_game_short_name_from_asset(asset)?;
+                let game_short_name = &get_game_short_name_from_asset(asset)?;
                 info!("After unzip work for {} - start", game_short_name);
 
                 if options.is_none()
@@ -529,6 +702,13 @@ impl REvilThings for REvilManager {
                         game_short_name.to_string(),
                     ))
                 })?;
+                let manager = get_release_manager_for_game(
+                    self.github_release_manager.as_deref(),
+                    &self.channel_release_managers,
+                    Some(game_config),
+                );
+                let release = manager.and_then(|manager| manager.getRelease());
+                let source = manager.map(|manager| manager.source_name());
 
                 // add version from asset to array or create new array with the asset version
                 if options.is_none()
@@ -539,7 +719,15 @@ impl REvilThings for REvilManager {
                             .iter()
                             .any(|option| *option == AfterUnzipOption::SkipSettingVersion)
                 {
-                    add_asset_ver_to_game_conf_ver(game_config, version, asset);
+                    let release =
+                        release.ok_or_else(|| Report::new(REvilManagerError::ReleaseIsEmpty))?;
+                    add_asset_ver_to_game_conf_ver(
+                        game_config,
+                        game_short_name,
+                        release,
+                        source,
+                        asset,
+                    );
                 }
 
                 // set NEXTGEN accordingly to an asset but only for the supported games
@@ -551,25 +739,6 @@ impl REvilThings for REvilManager {
                 // remove second, not needed runtime file as for example when switching between different runtime versions
                 // second file may persists therefore blocking loading OpenXR runtime from loading
                 remove_second_runtime_file(game_config)?;
-
-                // it is ok to unwrap as in add_asset_ver_to_game_conf_ver step we added array to that game config
-                let versions = game_config.versions.as_ref().unwrap();
-                let max_cache = self
-                    .config
-                    .main
-                    .max_cache_versions_per_game
-                    .unwrap_or(MAX_ZIP_FILES_PER_GAME_CACHE);
-                // TODO should iter here for surpassed vector and also should be changed in rescan_option as user dynamically can change max_cache in config file
-                //      p.s. now it slowly removes cache (after every download) maybe this is ok?
-                if versions.len() > max_cache.into() {
-                    let last_ver = versions.last().unwrap();
-                    cleanup_cache(last_ver, game_short_name)?;
-
-                    // after cleaning up cache remove last item from versions vector
-                    let mut versions = versions.clone();
-                    versions.pop();
-                    game_config.versions = Some(versions);
-                }
                 debug!("{:?}", game_config.versions);
                 info!("After unzip work for {game_short_name} - done");
                 Ok(())
@@ -584,6 +753,7 @@ impl REvilThings for REvilManager {
             });
         });
         self.state.selected_assets.drain(..);
+        self.apply_cache_policy();
 
         Ok(self)
     }
@@ -655,25 +825,7 @@ impl REvilThings for REvilManager {
         use SwitchActionReport::*;
         match what_next {
             ToggleNUnzipSave(short_name, second_asset_name) => {
-                self.toggle_nextgen(&short_name);
-                self.unzip_update::<fn(&OsStr) -> bool>(
-                    &short_name,
-                    &second_asset_name,
-                    None,
-                    None,
-                )?;
-                // it is required to populate selected_assets for after_unzip_work
-                self.state.selected_assets.push(ReleaseAsset {
-                    download_url: "".to_string(),
-                    name: second_asset_name,
-                });
-                self.after_unzip_work(Some(
-                    [
-                        AfterUnzipOption::SkipSettingVersion,
-                        AfterUnzipOption::SkipRemovingFromRequiredUpdates,
-                    ]
-                    .to_vec(),
-                ))?;
+                self.switch_type_from_cache(&short_name, second_asset_name)?;
                 self.save_config()?;
                 self.state.selected_option = Some(LabelOptions::Back);
                 return Ok(self);
@@ -688,15 +840,7 @@ impl REvilThings for REvilManager {
             }
             UnsetNonExistentToggleNRestart(game_short_name, second_asset_name) => {
                 self.toggle_nextgen(&game_short_name);
-                let game_conf = self.config.games.get_mut(&game_short_name).unwrap();
-                let first_set = game_conf.versions.as_mut().unwrap().first_mut().unwrap();
-                let position = first_set
-                    .iter()
-                    .skip(1)
-                    .position(|asset_name| *asset_name == second_asset_name)
-                    .unwrap();
-                first_set.remove(position);
-
+                self.remove_asset_from_latest_version(&game_short_name, &second_asset_name);
                 self.set_switch_as_version(&game_short_name);
                 self.save_config()?;
                 restart_program(run_after, game_short_name)
@@ -735,28 +879,7 @@ impl REvilThings for REvilManager {
                     "short_name- {} asset_name- {} version-{}",
                     short_name, asset_name, version
                 );
-                self.unzip_update::<fn(&OsStr) -> bool>(
-                    &short_name,
-                    &asset_name,
-                    Some(&version),
-                    None,
-                )?;
-                // it is required to populate selected_assets for after_unzip_work
-                self.state.selected_assets.push(ReleaseAsset {
-                    download_url: "".to_string(),
-                    name: asset_name,
-                });
-                self.after_unzip_work(Some(
-                    [
-                        AfterUnzipOption::SkipSettingVersion,
-                        AfterUnzipOption::SkipRemovingFromRequiredUpdates,
-                    ]
-                    .to_vec(),
-                ))?;
-                match self.config.games.get_mut(&short_name) {
-                    Some(game_config) => game_config.version_in_use = Some(version),
-                    None => (),
-                };
+                self.load_version_from_cache(&short_name, asset_name, version)?;
                 self.state.selected_option = Some(LabelOptions::Back);
             }
             Back => {
@@ -767,128 +890,721 @@ impl REvilThings for REvilManager {
         Ok(self)
     }
 
-    fn check_for_self_update(&mut self) -> DynResult<&mut Self> {
-        todo!()
-    }
-
-    fn self_update(&mut self) -> DynResult<&mut Self> {
-        todo!()
-    }
-
-    fn before_launch_procedure(&self, steam_id: &String) -> ResultManagerErr<String> {
-        let (game_short_name, game_config) = find_game_conf_by_steam_id(&self.config, steam_id)?;
-        info!("Before launch procedure - start");
-        if game_config.runtime.is_none() {
-            warn!("Mod runtime for {} is None", game_short_name);
-            return Ok(game_short_name.to_string());
+    fn install_release_if_chosen(&mut self) -> ResultManagerErr<&mut Self> {
+        if self.state.selected_option != Some(LabelOptions::InstallDifferentRelease) {
+            return Ok(self);
         }
-        if game_config.versions.is_none() || game_config.version_in_use.is_none() {
-            error!(
-                "Do you have mod installed for? {} Please select one to download",
-                game_short_name
-            );
-            return Err(Report::new(REvilManagerError::ModIsNotInstalled(
-                game_short_name.to_string(),
-            )));
+        let short_name = match self.dialogs.get_game_for_release(&self.config) {
+            Some(it) => it,
+            None => {
+                self.state.selected_option = Some(LabelOptions::Back);
+                return Ok(self);
+            }
+        };
+        let mut page = 1;
+        let mut releases: Vec<Release> = Vec::new();
+        loop {
+            // game following a channel gets releases of its channel
+            let fetched = self
+                .get_release_manager_for_game_mut(&short_name)?
+                .get_reframework_releases(page, RELEASES_PER_PAGE)
+                .map_err(to_checking_release_err)?;
+            let has_more = fetched.len() == RELEASES_PER_PAGE as usize;
+            releases.extend(fetched);
+            match self.dialogs.get_selected_release(&releases, has_more) {
+                LabelOptions::MoreReleases => page += 1,
+                LabelOptions::SelectRelease(version) => {
+                    let release = releases
+                        .iter()
+                        .find(|release| release.name == version)
+                        .cloned()
+                        .ok_or_else(|| {
+                            Report::new(REvilManagerError::ReleaseNotFound(version.clone()))
+                        })?;
+                    self.install_release_for_game(release, &short_name)?;
+                    self.save_config()?;
+                    break;
+                }
+                _ => break,
+            }
         }
-        let maybe_vec = game_config
-            .versions
-            .as_ref()
-            .unwrap()
-            .iter()
-            .find(|ver_set| {
-                ver_set.first().unwrap() == game_config.version_in_use.as_ref().unwrap()
-            });
+        self.state.selected_option = Some(LabelOptions::Back);
+        Ok(self)
+    }
 
-        let mut version_vec: &Vec<String> = &Vec::new();
-        if maybe_vec.is_none() {
-            warn!("Your version is not in cache anymore. Will try to get runtime from latest instead.");
-            version_vec = game_config.versions.as_ref().unwrap().first().unwrap();
-        } else {
-            info!(
-                "Checking runtime for {} version",
-                game_config.version_in_use.as_ref().unwrap()
-            );
-            version_vec = maybe_vec.unwrap();
+    fn pin_version_if_chosen(&mut self) -> ResultManagerErr<&mut Self> {
+        if self.state.selected_option != Some(LabelOptions::PinSection) {
+            return Ok(self);
         }
-        if version_vec.len() < 2 {
-            warn!("Mod version has no cache file");
-            return Ok(game_short_name.to_string());
+        match self.dialogs.get_pin_decision(&self.config) {
+            LabelOptions::PinVersion(short_name, version) => {
+                if let Some(game_config) = self.config.games.get_mut(&short_name) {
+                    info!("{} pinned to {} version", short_name, version);
+                    game_config.pin = Some(version);
+                }
+                self.save_config()?;
+            }
+            LabelOptions::Unpin(short_name) => {
+                if let Some(game_config) = self.config.games.get_mut(&short_name) {
+                    info!("{} unpinned", short_name);
+                    game_config.pin = None;
+                }
+                self.save_config()?;
+            }
+            _ => (),
         }
-        self.unzip_runtime_file_from_correct_version(game_config, version_vec, game_short_name)?;
-
-        info!("Before launch procedure - end");
-        Ok(game_short_name.to_string())
-    }
-
-    fn launch_game(&mut self) -> ResultManagerErr<&mut Self> {
-        if let Some(steam_id) = &self.state.selected_game_to_launch {
-            let game_short_name = self.before_launch_procedure(steam_id)?;
-
-            info!("Launching the game {}", game_short_name);
-            self.steam_menago
-                .run_game_via_steam_manager(steam_id)
-                .change_context(REvilManagerError::default())?
-        } else {
-            info!("Game to launch is none")
-        };
+        self.state.selected_option = Some(LabelOptions::Back);
         Ok(self)
     }
 
-    fn bind(
-        &mut self,
-        cb: impl Fn(&mut REvilManager) -> ResultManagerErr<&mut Self>,
-        log_level: Level,
-    ) -> &mut Self {
-        if self.state.skip_next {
-            return self;
+    fn uninstall_if_chosen(&mut self) -> ResultManagerErr<&mut Self> {
+        if self.state.selected_option != Some(LabelOptions::UninstallSection) {
+            return Ok(self);
         }
-        match cb(self) {
-            Ok(_it) => self,
-            Err(err) => {
-                self.state.skip_next = true;
-                log!(log_level, "{}", err);
-                debug!("Error {:?}", err);
-                self
-            }
+        if let LabelOptions::Uninstall(short_name) =
+            self.dialogs.get_uninstall_decision(&self.config)
+        {
+            self.uninstall_mod(&short_name)?;
+            self.save_config()?;
         }
+        self.state.selected_option = Some(LabelOptions::Back);
+        Ok(self)
     }
 
-    fn or_log_err(
+    // pinned games get their pinned version, games supporting both types without mod type set get preferred one
+    fn select_games_to_update(
         &mut self,
-        cb: impl Fn(&mut REvilManager) -> ResultManagerErr<&mut Self>,
-        log_level: Level,
-    ) -> &mut Self {
-        match cb(self) {
-            Ok(_it) => self,
-            Err(err) => {
-                log!(log_level, "{}", err);
-                debug!("Error {:?}", err);
-                self
+        games: &[ShortGameName],
+        prefer_nextgen: Option<bool>,
+    ) -> ResultManagerErr<&mut Self> {
+        for game_short_name in games {
+            let game_config = self.config.games.get(game_short_name).ok_or_else(|| {
+                Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                    game_short_name.to_string(),
+                ))
+            })?;
+            if let Some(pinned_version) = get_pinned_version(game_config) {
+                info!(
+                    "{} is pinned to {} version. Update skipped",
+                    game_short_name, pinned_version
+                );
+                self.install_pinned_version_if_needed(game_short_name, &pinned_version)?;
+                continue;
+            }
+            if !self
+                .state
+                .games_that_require_update
+                .contains(game_short_name)
+            {
+                info!("Update not required for {}", game_short_name);
+                continue;
+            }
+            let mut game_config = game_config.clone();
+            if game_config.nextgen.is_none() {
+                game_config.nextgen = prefer_nextgen;
             }
+            set_game_from_report_as_selected_to_download(
+                get_release_manager_for_game(
+                    self.github_release_manager.as_deref(),
+                    &self.channel_release_managers,
+                    Some(&game_config),
+                ),
+                self.state.selected_assets.as_mut(),
+                &game_config,
+                game_short_name,
+            )?;
         }
+        Ok(self)
     }
 
-    fn decision_loop(&mut self) -> ResultManagerErr<&mut Self> {
-        while self.state.selected_option == Some(LabelOptions::GoTop)
-            && self.state.selected_game_to_launch.is_none()
+    fn switch_type_for_game(&mut self, short_name: &str) -> ResultManagerErr<&mut Self> {
+        let game_config = self.config.games.get(short_name).ok_or_else(|| {
+            Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                short_name.to_string(),
+            ))
+        })?;
+        if !game_supports_both_editions(short_name) {
+            return Err(Report::new(REvilManagerError::SwitchTypeNotSupported(
+                short_name.to_string(),
+            )))
+            .attach_printable("Game doesn't support both Nextgen/Standard versions");
+        }
+        let latest_version = game_config
+            .versions
+            .as_ref()
+            .and_then(|versions| versions.first())
+            .map(|record| &record.version);
+        if latest_version.is_none()
+            || game_config.version_in_use.is_none()
+            || game_config.nextgen.is_none()
         {
-            debug!("Select download option");
-            self.ask_for_decision()
-                .and_then(|this| this.download_REFramework_update())
-                .and_then(|this| this.unzip_updates().after_unzip_work(None))
-                .and_then(|this| this.save_config())
-                .map(|_| Ok(()))
-                .unwrap_or_else(|err| {
-                    if err.current_context() == &REvilManagerError::NoGamesToUpdate {
-                        return Ok(());
-                    }
-                    Err(err)
-                })?;
-
-            self.state.selected_option = Some(LabelOptions::Back);
+            return Err(Report::new(REvilManagerError::ModIsNotInstalled(
+                short_name.to_string(),
+            )));
+        }
+        if latest_version != game_config.version_in_use.as_ref() {
+            return Err(Report::new(REvilManagerError::SwitchTypeNotSupported(
+                short_name.to_string(),
+            )))
+            .attach_printable(
+                "Only latest cached version can be switched. Use load-cache for older ones",
+            );
+        }
 
-            while self.state.selected_option == Some(LabelOptions::Back)
+        let what_next = get_switch_type_action(&self.config, &self.state, short_name.to_string())
+            .change_context(REvilManagerError::Other)?;
+        use SwitchActionReport::*;
+        let short_name = short_name.to_string();
+        match what_next {
+            ToggleNUnzipSave(short_name, second_asset_name) => {
+                return self
+                    .switch_type_from_cache(&short_name, second_asset_name)
+                    .and_then(|_| self.save_config());
+            }
+            ToggleNSetSwitchSaveRestart(_) => {
+                self.toggle_nextgen(&short_name);
+                self.set_switch_as_version(&short_name);
+            }
+            UnsetNonExistentToggleNRestart(_, second_asset_name) => {
+                self.toggle_nextgen(&short_name);
+                self.remove_asset_from_latest_version(&short_name, &second_asset_name);
+                self.set_switch_as_version(&short_name);
+            }
+            ToggleNSaveRestart(_) => self.toggle_nextgen(&short_name),
+            Early => return Ok(self),
+        }
+        // other mod type is not in cache so instead of restarting the program download it right away
+        self.update_game_now(&short_name)?;
+        self.save_config()
+    }
+
+    fn switch_runtime_for_game(
+        &mut self,
+        short_name: &str,
+        runtime: Runtime,
+    ) -> ResultManagerErr<&mut Self> {
+        let game_config = self.config.games.get_mut(short_name).ok_or_else(|| {
+            Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                short_name.to_string(),
+            ))
+        })?;
+        if game_config.versions.is_none() || game_config.version_in_use.is_none() {
+            return Err(Report::new(REvilManagerError::ModIsNotInstalled(
+                short_name.to_string(),
+            )));
+        }
+        match set_runtime_and_find_cached_version(game_config, short_name, runtime) {
+            Ok(pos) => {
+                // unwraps below are ok as position comes from versions of that game
+                let game_config = self.config.games.get(short_name).unwrap();
+                let version_vec = game_config.versions.as_ref().unwrap().get(pos).unwrap();
+                self.unzip_runtime_file_from_correct_version(
+                    game_config,
+                    version_vec,
+                    &short_name.to_string(),
+                )?;
+            }
+            Err(err) => match err.current_context() {
+                // version in use has been marked for update
+                DialogsErrors::NoCacheFile(_) => self.update_game_now(short_name)?,
+                _ => return Err(err).change_context(REvilManagerError::Other),
+            },
+        };
+        self.save_config()
+    }
+
+    fn load_from_cache_for_game(
+        &mut self,
+        short_name: &str,
+        version: &str,
+    ) -> ResultManagerErr<&mut Self> {
+        let game_config = self.config.games.get(short_name).ok_or_else(|| {
+            Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                short_name.to_string(),
+            ))
+        })?;
+        let asset_name = game_config
+            .versions
+            .as_ref()
+            .and_then(|versions| {
+                versions
+                    .iter()
+                    .find(|record| record.version == version && !record.assets.is_empty())
+            })
+            .and_then(|ver_set| find_asset_name_matching_mod_type(game_config, ver_set))
+            .ok_or_else(|| {
+                Report::new(REvilManagerError::CacheNotFoundForGivenVersion(
+                    version.to_string(),
+                ))
+            })?
+            .to_string();
+        self.load_version_from_cache(short_name, asset_name, version.to_string())?;
+        self.save_config()
+    }
+
+    fn select_game_to_launch(&mut self, short_name: &str) -> ResultManagerErr<&mut Self> {
+        let steam_id = self
+            .config
+            .games
+            .get(short_name)
+            .and_then(|game_config| game_config.steamId.clone())
+            .ok_or_else(|| {
+                Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                    short_name.to_string(),
+                ))
+            })?;
+        self.state.selected_game_to_launch = Some(steam_id);
+        Ok(self)
+    }
+
+    // game has to be known to the game registry, its location is valid when the game executable is there
+    fn register_game(&mut self, short_name: &str, location: &str) -> ResultManagerErr<&mut Self> {
+        let entry = with_game_registry(|registry| registry.find_by_short_name(short_name).cloned())
+            .ok_or_else(|| Report::new(REvilManagerError::UnknownGame(short_name.to_string())))
+            .attach_printable(
+                "Add a game section with steamId to config.toml to register other games",
+            )?;
+        let location = location.trim().trim_matches('"');
+        let executable = Path::new(location).join(&entry.executable);
+        if !executable.is_file() {
+            return Err(Report::new(REvilManagerError::GameExecutableNotFound(
+                executable.display().to_string(),
+            )));
+        }
+        let local_config = self
+            .local_provider
+            .get_local_report_for_game(location, short_name);
+        let game_config = self.config.games.entry(short_name.to_string()).or_default();
+        game_config.location = Some(location.to_string());
+        game_config.manual = Some(true);
+        game_config.steamId.get_or_insert(entry.steam_id);
+        game_config.runtime = local_config
+            .runtime
+            .or_else(|| game_config.runtime.take())
+            .or(Some(Runtime::OpenVR));
+        game_config.nextgen = local_config.nextgen;
+        if let Some(local_ver) = local_config.version {
+            if game_config.versions.is_none() {
+                game_config.versions = Some(vec![VersionRecord::new(&local_ver)]);
+                game_config.version_in_use = Some(local_ver);
+            }
+        }
+        info!("{} registered at {}", short_name, location);
+        Ok(self)
+    }
+
+    fn register_game_if_chosen(&mut self) -> ResultManagerErr<&mut Self> {
+        if self.state.selected_option != Some(LabelOptions::RegisterGameSection) {
+            return Ok(self);
+        }
+        if let Some((short_name, location)) = self.dialogs.get_game_to_register(&self.config) {
+            self.register_game(&short_name, &location)?;
+            self.save_config()?;
+        }
+        self.state.selected_option = Some(LabelOptions::Back);
+        Ok(self)
+    }
+
+    fn print_status(&mut self, format: &StatusFormat) -> ResultManagerErr<&mut Self> {
+        let latest_version = self
+            .github_release_manager
+            .as_ref()
+            .and_then(|manager| manager.getRelease())
+            .map(|release| release.name.to_string());
+        let channel_versions: HashMap<String, String> = self
+            .channel_release_managers
+            .iter()
+            .filter_map(|(channel, manager)| {
+                manager
+                    .getRelease()
+                    .map(|release| (channel.to_string(), release.name.to_string()))
+            })
+            .collect();
+        let report = StatusReport::new(
+            &self.config,
+            &self.state.games_that_require_update,
+            latest_version.as_deref(),
+            &channel_versions,
+        );
+        let output = match format {
+            StatusFormat::text => Ok(report.to_text()),
+            StatusFormat::json => report.to_json(),
+            StatusFormat::toml => report.to_toml(),
+        }
+        .map_err(|err| Report::new(REvilManagerError::Other).attach_printable(err.to_string()))?;
+        println!("{}", output);
+        Ok(self)
+    }
+
+    fn check_config(&mut self) -> ResultManagerErr<&mut Self> {
+        let problems = self
+            .config_provider
+            .check_file()
+            .change_context(REvilManagerError::LoadConfigError)?;
+        if problems.is_empty() {
+            println!("Config file is valid");
+            return Ok(self);
+        }
+        problems.iter().for_each(|problem| println!("{}", problem));
+        Err(Report::new(REvilManagerError::InvalidConfig(
+            problems.len(),
+        )))
+    }
+
+    fn restore_config(&mut self, number: u8) -> ResultManagerErr<&mut Self> {
+        let backup_path = self
+            .config_provider
+            .restore_backup(number)
+            .change_context(REvilManagerError::RestoreConfigError(number))?;
+        println!("Config restored from {}", backup_path);
+        Ok(self)
+    }
+
+    fn prune_cache(&mut self, dry_run: bool) -> ResultManagerErr<&mut Self> {
+        let evictions = find_cache_evictions(
+            &self.config.games,
+            &CachePolicy::from_main(&self.config.main),
+            SystemTime::now(),
+        );
+        if evictions.is_empty() {
+            println!("Nothing to remove from cache");
+            return Ok(self);
+        }
+        evictions.iter().for_each(|eviction| {
+            println!(
+                "{} {} {} ({})",
+                eviction.game_short_name,
+                eviction.version,
+                format_size(eviction.bytes),
+                eviction.reason
+            )
+        });
+        let total_bytes = evictions.iter().map(|eviction| eviction.bytes).sum();
+        if dry_run {
+            println!(
+                "{} versions, {} would be removed",
+                evictions.len(),
+                format_size(total_bytes)
+            );
+            return Ok(self);
+        }
+        evict_cache(&mut self.config.games, &evictions)?;
+        println!(
+            "{} versions, {} removed",
+            evictions.len(),
+            format_size(total_bytes)
+        );
+        Ok(self)
+    }
+
+    fn get_config_value(&mut self, key: &str) -> ResultManagerErr<&mut Self> {
+        let value = self
+            .config_provider
+            .get_value(key)
+            .map_err(to_config_value_error)?;
+        println!("{}", value);
+        Ok(self)
+    }
+
+    fn set_config_value(&mut self, key: &str, value: &str) -> ResultManagerErr<&mut Self> {
+        self.config_provider
+            .set_value(key, value)
+            .map_err(to_config_value_error)?;
+        println!("{} = {}", key, value);
+        Ok(self)
+    }
+
+    fn install_release_from_args(&mut self) -> ResultManagerErr<&mut Self> {
+        let game_short_name;
+        let selector;
+        let should_run_after;
+        unsafe {
+            let args = ARGS.as_ref().unwrap();
+            game_short_name = args.one.clone();
+            selector = args.release.clone().unwrap_or_else(|| "latest".to_string());
+            should_run_after = args.run.to_bool();
+        }
+        debug!("Args -one {}, --release {}", game_short_name, selector);
+        let steam_id = self
+            .config
+            .games
+            .get(&game_short_name)
+            .ok_or_else(|| {
+                Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                    game_short_name.clone(),
+                ))
+            })?
+            .steamId
+            .clone();
+        if should_run_after {
+            self.state.selected_game_to_launch = steam_id;
+        }
+        let manager = self.get_release_manager_for_game_mut(&game_short_name)?;
+        let release = find_release(&ReleaseSelector::from(&selector[..]), |page, per_page| {
+            manager.get_reframework_releases(page, per_page)
+        })
+        .or_else(|err| {
+            Err(Report::new(REvilManagerError::ReleaseNotFound(selector)))
+                .attach_printable(format!("{:?}", err))
+        })?;
+        info!(
+            "Installing release {} for {}",
+            release.name, game_short_name
+        );
+        self.install_release_for_game(release, &game_short_name)?;
+        Ok(self)
+    }
+
+    fn check_for_self_update(&mut self) -> ResultManagerErr<&mut Self> {
+        let current_version = env!("CARGO_PKG_VERSION");
+        info!("Checking if new REFresher version exists");
+        let mut manager = (self.refr_ctor)(SELF_REPO_OWNER, SELF_REPO_NAME);
+        manager.set_options(GithubOptions {
+            token: get_github_token(&self.config.main),
+            asset_host: self.config.main.asset_host.clone(),
+            channel: Some(SELF_UPDATE_CHANNEL.to_string()),
+            ..GithubOptions::default()
+        });
+        let releases = manager
+            .get_reframework_releases(1, SELF_UPDATE_RELEASES_TO_CHECK)
+            .map_err(|err| Report::new(REvilManagerError::SelfUpdateError(err.to_string())))?;
+        match find_newer_release(releases, current_version) {
+            Some(release) => {
+                info!(
+                    "New REFresher version {} is available, current one is {}",
+                    release.version, current_version
+                );
+                manager.set_release(Some(release.clone())).map_err(|err| {
+                    Report::new(REvilManagerError::SelfUpdateError(err.to_string()))
+                })?;
+                self.self_release_manager = Some(manager);
+                self.state.self_update_release = Some(release);
+                if self.config.main.autoupdate.unwrap_or_default() {
+                    return self.self_update();
+                }
+            }
+            None => debug!("REFresher {} is up to date", current_version),
+        };
+        Ok(self)
+    }
+
+    fn self_update(&mut self) -> ResultManagerErr<&mut Self> {
+        let (release, manager) = match (
+            &self.state.self_update_release,
+            self.self_release_manager.as_deref(),
+        ) {
+            (Some(release), Some(manager)) => (release, manager),
+            _ => {
+                debug!("No REFresher release to update to");
+                return Ok(self);
+            }
+        };
+        let asset = find_executable_asset(release).ok_or_else(|| {
+            Report::new(REvilManagerError::SelfUpdateError(format!(
+                "release {} has no executable for this system",
+                release.version
+            )))
+        })?;
+        info!("Updating REFresher to {}", release.version);
+        // the same download as for mod archives, so size and digest are checked before anything is replaced
+        let pb = ProgressBar::new(0);
+        pb.set_style(progress_style::getDownloadProgressStyle());
+        download_and_verify_asset(
+            &AssetDownload {
+                manager,
+                asset,
+                channel: Some(SELF_UPDATE_CHANNEL),
+            },
+            &pb,
+        )?;
+        pb.finish_and_clear();
+        let downloaded =
+            get_local_path_to_cache_folder(manager.getRelease(), None, Some(SELF_UPDATE_CHANNEL))
+                .map(|path| path.join(&asset.name))
+                .map_err(|_| Report::new(REvilManagerError::GetLocalPathToCacheErr))?;
+        let backup_path = env::current_exe()
+            .map_err(|err| err.into())
+            .and_then(|executable| install_executable(&executable, &downloaded))
+            .map_err(|err| Report::new(REvilManagerError::SelfUpdateError(err.to_string())))?;
+        if let Some(cache_dir) = downloaded.parent() {
+            fs::remove_dir_all(cache_dir).unwrap_or_else(|err| {
+                debug!("Couldn't remove {} Err {}", cache_dir.display(), err)
+            });
+        }
+        info!(
+            "REFresher updated to {}. Previous version is kept as {}",
+            release.version,
+            backup_path.display()
+        );
+        self.save_config()?;
+
+        restart_with_same_args()
+            .report()
+            .change_context(REvilManagerError::ErrorRestartingProgram)?;
+        Ok(self)
+    }
+
+    fn self_update_if_chosen(&mut self) -> ResultManagerErr<&mut Self> {
+        if !matches!(
+            self.state.selected_option,
+            Some(LabelOptions::SelfUpdate(_))
+        ) {
+            return Ok(self);
+        }
+        self.state.selected_option = Some(LabelOptions::Back);
+        // failed update leaves current version working so user can still launch a game
+        Ok(self.or_log_err(|this| this.self_update(), Level::Error))
+    }
+
+    fn rollback_self_update(&mut self) -> ResultManagerErr<&mut Self> {
+        let backup_path = env::current_exe()
+            .map_err(|err| err.into())
+            .and_then(|executable| rollback_executable(&executable))
+            .map_err(|err| Report::new(REvilManagerError::SelfUpdateError(err.to_string())))?;
+        println!(
+            "Previous REFresher version restored. The replaced one is kept as {}",
+            backup_path.display()
+        );
+        println!("Set autoupdate = false in config.toml to stay on this version");
+        Ok(self)
+    }
+
+    fn before_launch_procedure(&self, steam_id: &String) -> ResultManagerErr<String> {
+        let (game_short_name, game_config) = find_game_conf_by_steam_id(&self.config, steam_id)?;
+        info!("Before launch procedure - start");
+        if game_config.runtime.is_none() {
+            warn!("Mod runtime for {} is None", game_short_name);
+            return Ok(game_short_name.to_string());
+        }
+        // empty versions list is the same as no versions at all
+        let (version_in_use, latest_record) = match (
+            game_config.version_in_use.as_ref(),
+            game_config
+                .versions
+                .as_ref()
+                .and_then(|versions| versions.first()),
+        ) {
+            (Some(version_in_use), Some(latest_record)) => (version_in_use, latest_record),
+            _ => {
+                error!(
+                    "Do you have mod installed for? {} Please select one to download",
+                    game_short_name
+                );
+                return Err(Report::new(REvilManagerError::ModIsNotInstalled(
+                    game_short_name.to_string(),
+                )));
+            }
+        };
+        let maybe_vec = game_config
+            .versions
+            .iter()
+            .flatten()
+            .find(|record| &record.version == version_in_use);
+
+        let version_vec = match maybe_vec {
+            Some(record) => {
+                info!("Checking runtime for {} version", version_in_use);
+                record
+            }
+            None => {
+                warn!("Your version is not in cache anymore. Will try to get runtime from latest instead.");
+                latest_record
+            }
+        };
+        if version_vec.assets.is_empty() {
+            warn!("Mod version has no cache file");
+            return Ok(game_short_name.to_string());
+        }
+        self.unzip_runtime_file_from_correct_version(game_config, version_vec, game_short_name)?;
+
+        info!("Before launch procedure - end");
+        Ok(game_short_name.to_string())
+    }
+
+    fn launch_game(&mut self) -> ResultManagerErr<&mut Self> {
+        if let Some(steam_id) = &self.state.selected_game_to_launch {
+            let game_short_name = self.before_launch_procedure(steam_id)?;
+            let game_config = &self.config.games[&game_short_name];
+            let extra_args = unsafe { ARGS.as_ref().and_then(|args| args.game_args.clone()) };
+            let launch_args =
+                combine_launch_args(game_config.runArgs.as_deref(), extra_args.as_deref());
+
+            info!(
+                "Launching the game {} with args {:?}",
+                game_short_name, launch_args
+            );
+            if game_config.manual.unwrap_or_default() {
+                let executable = get_game_executable(&game_short_name, game_config)?;
+                self.local_provider
+                    .run_game_exe(&executable, &launch_args)
+                    .map_err(|err| {
+                        Report::new(REvilManagerError::Other).attach_printable(err.to_string())
+                    })?
+            } else {
+                self.steam_menago
+                    .run_game_via_steam_manager(steam_id, &launch_args)
+                    .change_context(REvilManagerError::default())?
+            }
+        } else {
+            info!("Game to launch is none")
+        };
+        Ok(self)
+    }
+
+    fn bind(
+        &mut self,
+        cb: impl Fn(&mut REvilManager) -> ResultManagerErr<&mut Self>,
+        log_level: Level,
+    ) -> &mut Self {
+        if self.state.skip_next {
+            return self;
+        }
+        match cb(self) {
+            Ok(_it) => self,
+            Err(err) => {
+                self.state.skip_next = true;
+                log!(log_level, "{}", err);
+                debug!("Error {:?}", err);
+                self
+            }
+        }
+    }
+
+    fn or_log_err(
+        &mut self,
+        cb: impl Fn(&mut REvilManager) -> ResultManagerErr<&mut Self>,
+        log_level: Level,
+    ) -> &mut Self {
+        match cb(self) {
+            Ok(_it) => self,
+            Err(err) => {
+                log!(log_level, "{}", err);
+                debug!("Error {:?}", err);
+                self
+            }
+        }
+    }
+
+    fn decision_loop(&mut self) -> ResultManagerErr<&mut Self> {
+        while self.state.selected_option == Some(LabelOptions::GoTop)
+            && self.state.selected_game_to_launch.is_none()
+        {
+            debug!("Select download option");
+            self.ask_for_decision()
+                .and_then(|this| this.download_REFramework_update())
+                .and_then(|this| this.unzip_updates().after_unzip_work(None))
+                .and_then(|this| this.save_config())
+                .map(|_| Ok(()))
+                .unwrap_or_else(|err| {
+                    if err.current_context() == &REvilManagerError::NoGamesToUpdate {
+                        return Ok(());
+                    }
+                    Err(err)
+                })?;
+
+            self.state.selected_option = Some(LabelOptions::Back);
+
+            while self.state.selected_option == Some(LabelOptions::Back)
                 && self.state.selected_game_to_launch.is_none()
             {
                 debug!("Select decision");
@@ -903,6 +1619,11 @@ impl REvilThings for REvilManager {
                 self.main_loop()
                     .and_then(|this| this.ask_for_switch_type_decision(RunAfter::no))
                     .and_then(|this| this.load_from_cache_if_chosen())
+                    .and_then(|this| this.install_release_if_chosen())
+                    .and_then(|this| this.pin_version_if_chosen())
+                    .and_then(|this| this.uninstall_if_chosen())
+                    .and_then(|this| this.register_game_if_chosen())
+                    .and_then(|this| this.self_update_if_chosen())
                     .and_then(|this| this.rescan_option())
                     .and_then(|this| this.ask_for_switch_runtime_if_needed())
                     .and_then(|this| this.set_games_that_require_update())?;
@@ -924,7 +1645,10 @@ impl REvilThings for REvilManager {
         pb.enable_steady_tick(Duration::from_millis(80).as_secs());
         pb.set_style(progress_style::getProgressStyle());
         for (short_name, config) in self.config.games.iter_mut() {
-            let game_location = config.location.as_ref().unwrap();
+            // sections only overriding the game registry have no installed game to scan
+            let Some(game_location) = config.location.as_ref() else {
+                continue;
+            };
             pb.set_message(format!("Loading config from {} ...", game_location));
             pb.tick();
             let local_config = self
@@ -936,49 +1660,31 @@ impl REvilThings for REvilManager {
 
             if let Some(local_ver_hash) = local_config.version {
                 if let Some(versions) = config.versions.as_mut() {
-                    if let Some(first_set) = versions.iter().find(|ver_set| {
-                        ver_set
-                            .first()
-                            .map(|full_ver_or_hash| {
-                                full_ver_or_hash
-                                    .split_once(HASH_DELIMITER)
-                                    .map(|(_, hash)| hash == local_ver_hash)
-                                    // below is check because there may be only hash
-                                    .unwrap_or_else(|| full_ver_or_hash == &local_ver_hash)
-                            })
-                            .unwrap_or_default()
+                    if let Some(record) = versions.iter().find(|record| {
+                        record
+                            .version
+                            .split_once(HASH_DELIMITER)
+                            .map(|(_, hash)| hash == local_ver_hash)
+                            // below is check because there may be only hash
+                            .unwrap_or_else(|| record.version == local_ver_hash)
                     }) {
                         // if local_ver is already in versions vector then we want full version info not only hash
-                        config.version_in_use = Some(first_set.first().unwrap().to_string());
+                        config.version_in_use = Some(record.version.clone());
                     } else {
                         // if there is not in versions then push this hash as a version
-                        versions.insert(0, [local_ver_hash.to_string()].to_vec());
+                        versions.insert(0, VersionRecord::new(&local_ver_hash));
                         config.version_in_use = Some(local_ver_hash);
-
-                        let max_cache = self
-                            .config
-                            .main
-                            .max_cache_versions_per_game
-                            .unwrap_or(MAX_ZIP_FILES_PER_GAME_CACHE);
-                        if versions.len() > max_cache.into() {
-                            let last_ver = versions.last().unwrap();
-                            cleanup_cache(last_ver, short_name)?;
-
-                            // after cleaning up cache remove last item from versions vector
-                            let mut versions = versions.clone();
-                            versions.pop();
-                            config.versions = Some(versions);
-                        }
                     }
                 } else {
                     // if no version array at all then create one
-                    config.versions = Some([[local_ver_hash.to_string()].to_vec()].to_vec());
+                    config.versions = Some(vec![VersionRecord::new(&local_ver_hash)]);
                     config.version_in_use = Some(local_ver_hash);
                 }
             }
             config.nextgen = local_config.nextgen;
         }
         pb.finish_with_message("Done");
+        self.apply_cache_policy();
 
         self.state.selected_option = Some(LabelOptions::Back);
         trace!("Full config: \n {:#?}", self.config);
@@ -991,7 +1697,7 @@ impl REvilManager {
     fn unzip_runtime_file_from_correct_version(
         &self,
         game_config: &GameConfig,
-        version_vec: &Vec<String>,
+        version_vec: &VersionRecord,
         game_short_name: &String,
     ) -> ResultManagerErr<()> {
         let game_dir = game_config
@@ -1002,20 +1708,9 @@ impl REvilManager {
         let runtime = game_config.runtime.as_ref().unwrap();
         if !game_dir.join(runtime.as_local_dll()).exists() {
             let should_skip_all_except = |file: &OsStr| file != OsStr::new(&runtime.as_local_dll());
-            let ver = &version_vec[0];
-
-            let file_name = version_vec.iter().skip(1).find(|name| {
-                is_asset_tdb(
-                    game_short_name,
-                    &ReleaseAsset {
-                        name: name.to_string(),
-                        ..Default::default()
-                    },
-                )
-                .and_then(|is_tdb| game_config.nextgen.map(|nextgen| (is_tdb, nextgen)))
-                .map(|(is_tdb, nextgen)| (is_tdb && !nextgen) || (!is_tdb && nextgen)) // if asset is none TDB/NG or nextgen field is missing then just return 1 st item
-                .unwrap_or(true)
-            });
+            let ver = &version_vec.version;
+
+            let file_name = find_asset_name_matching_mod_type(game_config, version_vec);
 
             // TODO should be safe to unwrap below but maybe some tests?
             let file_name = file_name.unwrap();
@@ -1034,6 +1729,300 @@ impl REvilManager {
 }
 
 impl REvilManager {
+    fn switch_type_from_cache(
+        &mut self,
+        short_name: &String,
+        second_asset_name: String,
+    ) -> ResultManagerErr<()> {
+        self.toggle_nextgen(short_name);
+        let latest_version = self
+            .config
+            .games
+            .get(short_name)
+            .and_then(|game_config| game_config.versions.as_ref())
+            .and_then(|versions| versions.first())
+            .map(|record| record.version.clone());
+        self.unzip_update::<fn(&OsStr) -> bool>(
+            short_name,
+            &second_asset_name,
+            latest_version.as_deref(),
+            None,
+        )?;
+        // it is required to populate selected_assets for after_unzip_work
+        self.state.selected_assets.push(ReleaseAsset {
+            download_url: "".to_string(),
+            name: second_asset_name,
+        });
+        self.after_unzip_work(Some(
+            [
+                AfterUnzipOption::SkipSettingVersion,
+                AfterUnzipOption::SkipRemovingFromRequiredUpdates,
+            ]
+            .to_vec(),
+        ))?;
+        Ok(())
+    }
+
+    // removes everything over cache limits in one pass, cache errors are only logged
+    fn apply_cache_policy(&mut self) {
+        let evictions = find_cache_evictions(
+            &self.config.games,
+            &CachePolicy::from_main(&self.config.main),
+            SystemTime::now(),
+        );
+        if evictions.is_empty() {
+            return;
+        }
+        match evict_cache(&mut self.config.games, &evictions) {
+            Ok(()) => info!("Removed {} versions from cache", evictions.len()),
+            Err(err) => warn!("Cache cleanup failed {:?}", err),
+        };
+    }
+
+    fn remove_asset_from_latest_version(&mut self, short_name: &String, asset_name: &String) {
+        if let Some(first_set) = self
+            .config
+            .games
+            .get_mut(short_name)
+            .and_then(|game_config| game_config.versions.as_mut())
+            .and_then(|versions| versions.first_mut())
+        {
+            first_set.assets.retain(|asset| &asset.name != asset_name);
+        }
+    }
+
+    // non-interactive equivalent of restarting the program with --one option
+    fn update_game_now(&mut self, short_name: &str) -> ResultManagerErr<()> {
+        if self.github_release_manager.is_none() {
+            self.check_for_REFramework_update()?;
+        } else {
+            self.set_games_that_require_update()?;
+        }
+        self.select_games_to_update(&[short_name.to_string()], None)?
+            .download_REFramework_update()?
+            .unzip_updates()
+            .after_unzip_work(None)?;
+        Ok(())
+    }
+
+    // removes files listed in install manifest (or if there is no manifest then files of cached archive of version in use)
+    fn uninstall_mod(&mut self, short_name: &str) -> ResultManagerErr<()> {
+        let game_config = self.config.games.get(short_name).ok_or_else(|| {
+            Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                short_name.to_string(),
+            ))
+        })?;
+        let location = game_config
+            .location
+            .as_ref()
+            .ok_or_else(|| Report::new(REvilManagerError::GameLocationMissing))?;
+        let manifest_path = get_manifest_path(short_name);
+        let files = match load_manifest(&manifest_path).or_else(|err| {
+            Err(Report::new(REvilManagerError::UninstallError(
+                short_name.to_string(),
+            )))
+            .attach_printable(format!("{:?}", err))
+        })? {
+            Some(files) => files,
+            None => {
+                warn!(
+                    "No install manifest for {}. Using file list of cached mod archive",
+                    short_name
+                );
+                get_files_from_cached_archive(short_name, game_config)?
+            }
+        };
+        // user edited settings and scripts stay in the game folder, the same as during update
+        let protected_paths = ProtectedPaths::from_config(&self.config.main, game_config);
+        let files: Vec<String> = files
+            .into_iter()
+            .filter(|file| !protected_paths.is_protected(Path::new(file)))
+            .collect();
+
+        let removed = remove_installed_files(Path::new(location), &files).or_else(|err| {
+            Err(Report::new(REvilManagerError::UninstallError(
+                short_name.to_string(),
+            )))
+            .attach_printable(format!("{:?}", err))
+        })?;
+        remove_manifest(&manifest_path).unwrap_or_else(|err| {
+            warn!(
+                "Couldn't remove manifest {} Err: {}",
+                manifest_path.display(),
+                err
+            )
+        });
+
+        if let Some(game_config) = self.config.games.get_mut(short_name) {
+            game_config.versions = None;
+            game_config.version_in_use = None;
+            game_config.pin = None;
+        }
+        remove_game_from_update_needed_ones(&mut self.state.games_that_require_update, short_name);
+        info!(
+            "Mod uninstalled from {}. Removed {} files",
+            short_name, removed
+        );
+        Ok(())
+    }
+
+    fn load_version_from_cache(
+        &mut self,
+        short_name: &str,
+        asset_name: String,
+        version: String,
+    ) -> ResultManagerErr<()> {
+        self.unzip_update::<fn(&OsStr) -> bool>(short_name, &asset_name, Some(&version), None)?;
+        // it is required to populate selected_assets for after_unzip_work
+        self.state.selected_assets.push(ReleaseAsset {
+            download_url: "".to_string(),
+            name: asset_name,
+        });
+        self.after_unzip_work(Some(
+            [
+                AfterUnzipOption::SkipSettingVersion,
+                AfterUnzipOption::SkipRemovingFromRequiredUpdates,
+            ]
+            .to_vec(),
+        ))?;
+        if let Some(game_config) = self.config.games.get_mut(short_name) {
+            game_config.version_in_use = Some(version);
+        };
+        Ok(())
+    }
+
+    // manager of the channel the game follows, games without a channel use the default one
+    fn get_release_manager_for_game_mut(
+        &mut self,
+        game_short_name: &str,
+    ) -> ResultManagerErr<&mut Box<dyn ManageGithub>> {
+        let channel = self
+            .config
+            .games
+            .get(game_short_name)
+            .and_then(get_game_channel);
+        match channel {
+            Some(channel) => self.channel_release_managers.get_mut(channel),
+            None => self.github_release_manager.as_mut(),
+        }
+        .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))
+    }
+
+    // loads pinned version from cache or if it is not there then downloads its release
+    fn install_pinned_version_if_needed(
+        &mut self,
+        game_short_name: &str,
+        pinned_version: &str,
+    ) -> ResultManagerErr<()> {
+        let game_config = self.config.games.get(game_short_name).ok_or_else(|| {
+            Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                game_short_name.to_string(),
+            ))
+        })?;
+        if game_config.version_in_use.as_deref() == Some(pinned_version) {
+            debug!("Pinned version {} is already in use", pinned_version);
+            return Ok(());
+        }
+        let cached_asset_name = game_config
+            .versions
+            .as_ref()
+            .and_then(|versions| {
+                versions
+                    .iter()
+                    .find(|record| record.version == pinned_version)
+            })
+            .and_then(|ver_set| find_asset_name_matching_mod_type(game_config, ver_set))
+            .filter(|asset_name| {
+                get_local_path_to_cache_folder(
+                    None,
+                    Some(pinned_version),
+                    get_game_channel(game_config),
+                )
+                .map(|path| path.join(asset_name).exists())
+                .unwrap_or_default()
+            })
+            .cloned();
+
+        if let Some(asset_name) = cached_asset_name {
+            info!("Loading pinned version {} from cache", pinned_version);
+            return self.load_version_from_cache(
+                game_short_name,
+                asset_name,
+                pinned_version.to_string(),
+            );
+        }
+
+        info!(
+            "Pinned version {} is not in cache. Looking for its release",
+            pinned_version
+        );
+        let manager = self.get_release_manager_for_game_mut(game_short_name)?;
+        let release = find_release(
+            &ReleaseSelector::Tag(pinned_version.to_string()),
+            |page, per_page| manager.get_reframework_releases(page, per_page),
+        )
+        .or_else(|err| {
+            Err(Report::new(REvilManagerError::ReleaseNotFound(
+                pinned_version.to_string(),
+            )))
+            .attach_printable(format!("{:?}", err))
+        })?;
+        self.install_release_for_game(release, game_short_name)
+    }
+
+    // runs the download -> unzip -> after unzip pipeline for a release different than the latest one
+    // and sets the latest release back afterwards so update checks keep comparing against it
+    fn install_release_for_game(
+        &mut self,
+        release: Release,
+        game_short_name: &str,
+    ) -> ResultManagerErr<()> {
+        let manager = self.get_release_manager_for_game_mut(game_short_name)?;
+        let latest_release = manager.getRelease().cloned();
+        manager.set_release(Some(release)).or_else(|err| {
+            Err(Report::new(REvilManagerError::ReleaseIsEmpty))
+                .attach_printable(format!("{:?}", err))
+        })?;
+
+        let result = self
+            .config
+            .games
+            .get(game_short_name)
+            .ok_or_else(|| {
+                Report::new(REvilManagerError::GameNotFoundForGivenShortName(
+                    game_short_name.to_string(),
+                ))
+            })
+            .and_then(|game_config| {
+                set_game_from_report_as_selected_to_download(
+                    get_release_manager_for_game(
+                        self.github_release_manager.as_deref(),
+                        &self.channel_release_managers,
+                        Some(game_config),
+                    ),
+                    self.state.selected_assets.as_mut(),
+                    game_config,
+                    &game_short_name.to_string(),
+                )
+            })
+            .and_then(|_| self.download_REFramework_update())
+            .and_then(|this| {
+                this.unzip_updates().after_unzip_work(Some(
+                    [AfterUnzipOption::SkipRemovingFromRequiredUpdates].to_vec(),
+                ))
+            })
+            .map(|_| ());
+        self.state.selected_assets.drain(..);
+
+        self.get_release_manager_for_game_mut(game_short_name)?
+            .set_release(latest_release)
+            .or_else(|err| {
+                Err(Report::new(REvilManagerError::ReleaseIsEmpty))
+                    .attach_printable(format!("{:?}", err))
+            })?;
+        result
+    }
+
     fn toggle_nextgen(&mut self, short_name: &String) {
         let game_conf = self.config.games.get_mut(short_name).unwrap();
         let nextgen = game_conf.nextgen.as_mut().unwrap();
@@ -1041,32 +2030,56 @@ impl REvilManager {
     }
 
     fn set_switch_as_version(&mut self, short_name: &String) {
-        let versions = self
+        // without any version the game is downloaded by update check anyway
+        if let Some(first_set) = self
             .config
             .games
             .get_mut(short_name)
-            .unwrap()
-            .versions
-            .as_mut()
-            .unwrap();
-        let first_set = versions.first_mut().unwrap();
-        let version = first_set.first_mut().unwrap();
-        *version = SWITCH_IDENTIFIER.to_string();
+            .and_then(|game_config| game_config.versions.as_mut())
+            .and_then(|versions| versions.first_mut())
+        {
+            first_set.pending = Some(PendingAction::switch);
+        }
     }
 
     fn set_games_that_require_update(&mut self) -> ResultManagerErr<()> {
         let manager = self
             .github_release_manager
-            .as_mut()
+            .as_ref()
             .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
-        let release = manager.getRelease();
+        let channel_managers = &self.channel_release_managers;
         self.state.games_that_require_update.drain(..);
         self.config
             .games
             .iter()
             .try_for_each(|(short_name, game)| -> ResultManagerErr<()> {
-                if game.versions.is_some() {
-                    let latest_local_version = game.versions.as_ref().unwrap().first().unwrap();
+                if game.location.is_none() {
+                    debug!("{} has no game location. Skipping update check", short_name);
+                    return Ok(());
+                }
+                if let Some(pinned_version) = get_pinned_version(game) {
+                    debug!(
+                        "{} is pinned to {} version. Skipping update check",
+                        short_name, pinned_version
+                    );
+                    return Ok(());
+                }
+                let release = match get_game_channel(game) {
+                    Some(channel) => match channel_managers.get(channel) {
+                        Some(channel_manager) => channel_manager.getRelease(),
+                        None => {
+                            debug!(
+                                "Release of {} channel not found. Skipping update check for {}",
+                                channel, short_name
+                            );
+                            return Ok(());
+                        }
+                    },
+                    None => manager.getRelease(),
+                };
+                if let Some(latest_local_version) =
+                    game.versions.as_ref().and_then(|versions| versions.first())
+                {
                     let latest_github_version = release
                         .as_ref()
                         .ok_or_else(|| Report::new(REvilManagerError::ReleaseIsEmpty))?
@@ -1077,10 +2090,13 @@ impl REvilManager {
                         latest_local_version, latest_github_version, short_name
                     );
 
-                    let is_rnewer = isRepoVersionNewer(
-                        latest_local_version.first().unwrap(),
-                        latest_github_version,
-                    );
+                    // version marked for switch/update has to be downloaded again
+                    let is_rnewer = match latest_local_version.pending {
+                        Some(_) => Some(true),
+                        None => {
+                            isRepoVersionNewer(&latest_local_version.version, latest_github_version)
+                        }
+                    };
                     if let Some(is) = is_rnewer {
                         is.then(|| {
                             self.state
@@ -1115,7 +2131,7 @@ impl REvilManager {
 }
 
 fn set_game_from_report_as_selected_to_download(
-    github_release_manager: Option<&Box<dyn ManageGithub<REFRGithub>>>,
+    github_release_manager: Option<&dyn ManageGithub<REFRGithub>>,
     selected_assets: &mut Vec<ReleaseAsset>,
     game_config: &GameConfig,
     game_short_name: &String,
@@ -1161,6 +2177,293 @@ fn set_game_from_report_as_selected_to_download(
     Ok(())
 }
 
+// returns version the game is pinned to, "current" pin resolves to version in use
+pub fn get_pinned_version(game_config: &GameConfig) -> Option<String> {
+    let pin = game_config.pin.as_ref()?;
+    if pin == PIN_CURRENT {
+        return game_config.version_in_use.clone();
+    }
+    Some(pin.to_string())
+}
+
+// if asset is none TDB/NG or nextgen field is missing then just return 1st asset from version set
+fn find_asset_name_matching_mod_type<'a>(
+    game_config: &GameConfig,
+    record: &'a VersionRecord,
+) -> Option<&'a String> {
+    record
+        .assets
+        .iter()
+        .find(|asset| match (asset.edition, game_config.nextgen) {
+            (Some(edition), Some(nextgen)) => (edition == Edition::nextgen) == nextgen,
+            _ => true,
+        })
+        .map(|asset| &asset.name)
+}
+
+// rate limit gets its own error so user knows when to try again
+fn to_checking_release_err(err: Box<dyn std::error::Error>) -> Report<REvilManagerError> {
+    match err.downcast_ref::<REFRGithubError>() {
+        Some(REFRGithubError::RateLimitExceeded(rate_limit)) => Report::new(
+            REvilManagerError::GithubRateLimitExceeded(rate_limit.to_string()),
+        ),
+        _ => Report::new(REvilManagerError::CheckingNewReleaseErr)
+            .attach_printable(format!("{:?}", err)),
+    }
+}
+
+// asset together with the manager of the release channel it comes from
+struct AssetDownload<'a> {
+    manager: &'a dyn ManageGithub<REFRGithub>,
+    asset: &'a ReleaseAsset,
+    channel: Option<&'a str>,
+}
+
+// runs up to max_parallel downloads at once, results are in the same order as assets
+fn download_assets_concurrently(
+    assets: &[AssetDownload],
+    max_parallel: u8,
+) -> Vec<(String, ResultManagerErr<()>)> {
+    let multi_progress = MultiProgress::new();
+    let bars: Vec<ProgressBar> = assets
+        .iter()
+        .map(|AssetDownload { asset, .. }| {
+            let pb = multi_progress.add(ProgressBar::new(0));
+            pb.set_style(progress_style::getDownloadProgressStyle());
+            pb.set_message(format!("{} queued", asset.name));
+            pb
+        })
+        .collect();
+    let next_asset = AtomicUsize::new(0);
+    let workers = usize::from(max_parallel.max(1)).min(assets.len());
+
+    let mut results: Vec<(usize, (String, ResultManagerErr<()>))> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = Vec::new();
+                    loop {
+                        let index = next_asset.fetch_add(1, atomic::Ordering::SeqCst);
+                        let download = match assets.get(index) {
+                            Some(it) => it,
+                            None => break,
+                        };
+                        let asset = download.asset;
+                        let pb = &bars[index];
+                        let result = download_and_verify_asset(download, pb);
+                        match result {
+                            Ok(()) => pb.finish_with_message(format!("{} ready", asset.name)),
+                            Err(_) => pb.abandon_with_message(format!("{} failed", asset.name)),
+                        };
+                        results.push((index, (asset.name.to_string(), result)));
+                    }
+                    results
+                })
+            })
+            .collect();
+        // bars are drawn only while joined
+        multi_progress
+            .join()
+            .unwrap_or_else(|err| debug!("Progress draw error {}", err));
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("download thread panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+// downloads asset unless valid one is already in cache. Asset which fails verification is removed and downloaded again
+fn download_and_verify_asset(
+    download: &AssetDownload,
+    progress: &ProgressBar,
+) -> ResultManagerErr<()> {
+    let AssetDownload {
+        manager,
+        asset,
+        channel,
+    } = *download;
+    let path = get_local_path_to_cache_folder(manager.getRelease(), None, channel)
+        .map(|path| path.join(&asset.name))
+        .map_err(|_| Report::new(REvilManagerError::GetLocalPathToCacheErr))?;
+    if path.exists() {
+        match manager.verify_release_asset(asset) {
+            Ok(()) => {
+                debug!("{} is already in cache. Skipping download", asset.name);
+                progress.set_message(format!("{} is already in cache", asset.name));
+                return Ok(());
+            }
+            Err(err) => {
+                warn!(
+                    "Cached {} failed verification: {} Downloading it again",
+                    asset.name, err
+                );
+                remove_broken_asset(&path)?;
+            }
+        }
+    }
+
+    let mut download_error = None;
+    for attempt in 1..=MAX_DOWNLOAD_ATTEMPTS {
+        // interrupted download leaves .part file which next attempt resumes
+        if let Err(err) = manager.download_release_asset(asset, progress) {
+            warn!(
+                "Download of {} failed (attempt {}/{}): {}",
+                asset.name, attempt, MAX_DOWNLOAD_ATTEMPTS, err
+            );
+            download_error = Some(err);
+            continue;
+        }
+        download_error = None;
+        match manager.verify_release_asset(asset) {
+            Ok(()) => return Ok(()),
+            Err(err) => {
+                warn!(
+                    "Downloaded {} failed verification (attempt {}/{}): {}",
+                    asset.name, attempt, MAX_DOWNLOAD_ATTEMPTS, err
+                );
+                remove_broken_asset(&path)?;
+            }
+        }
+    }
+    if let Some(err) = download_error {
+        return Err(Report::new(REvilManagerError::DownloadAssetError(
+            asset.name.clone(),
+        )))
+        .attach_printable(format!("{:?}", err));
+    }
+    Err(Report::new(REvilManagerError::AssetVerificationFailed(
+        asset.name.clone(),
+    )))
+}
+
+fn remove_broken_asset(path: &Path) -> ResultManagerErr<()> {
+    fs::remove_file(path)
+        .report()
+        .change_context(REvilManagerError::RemoveZipAssetFromCacheErr(
+            path.display().to_string(),
+        ))
+}
+
+// corrupted zip should not stay in cache as it would be picked up again i.e. by load from cache
+fn remove_corrupted_zip(err: Report<UnzipError>, path_to_zip: &Path) -> Report<UnzipError> {
+    if matches!(err.current_context(), UnzipError::corrupted_archive) {
+        warn!(
+            "{} is corrupted. Removing it from cache",
+            path_to_zip.display()
+        );
+        remove_broken_asset(path_to_zip).unwrap_or_else(|err| debug!("{:?}", err));
+    }
+    err
+}
+
+// keeps manifest of installed files so mod can be uninstalled. In clean install mode files of previous build
+// which are missing from the new one are removed from game folder unless they are protected
+fn record_installed_files(
+    game_short_name: &str,
+    location: &str,
+    written_files: &[PathBuf],
+    clean_install: bool,
+    protected_paths: &ProtectedPaths,
+) {
+    let location = Path::new(location);
+    let written_files = to_relative_paths(location, written_files);
+    let result = update_manifest(
+        &get_manifest_path(game_short_name),
+        &written_files,
+        clean_install,
+    )
+    .and_then(|obsolete_files| {
+        let obsolete_files: Vec<String> = obsolete_files
+            .into_iter()
+            .filter(|file| !protected_paths.is_protected(Path::new(file)))
+            .collect();
+        if obsolete_files.is_empty() {
+            return Ok(());
+        }
+        info!(
+            "Clean install - removing {} files of previous build from {}",
+            obsolete_files.len(),
+            game_short_name
+        );
+        remove_installed_files(location, &obsolete_files).map(|_| ())
+    });
+    if let Err(err) = result {
+        warn!(
+            "Couldn't update install manifest for {} Err: {}",
+            game_short_name, err
+        );
+    }
+}
+
+fn get_files_from_cached_archive(
+    short_name: &str,
+    game_config: &GameConfig,
+) -> ResultManagerErr<Vec<String>> {
+    let not_found = || {
+        Report::new(REvilManagerError::InstallManifestNotFound(
+            short_name.to_string(),
+        ))
+    };
+    let version_in_use = game_config.version_in_use.as_ref().ok_or_else(not_found)?;
+    let ver_set = game_config
+        .versions
+        .as_ref()
+        .and_then(|versions| {
+            versions
+                .iter()
+                .find(|record| &record.version == version_in_use)
+        })
+        .ok_or_else(not_found)?;
+    let asset_name =
+        find_asset_name_matching_mod_type(game_config, ver_set).ok_or_else(not_found)?;
+    let path =
+        get_local_path_to_cache_folder(None, Some(version_in_use), get_game_channel(game_config))
+            .map_err(|_| Report::new(REvilManagerError::GetLocalPathToCacheErr))?
+            .join(asset_name);
+    if !path.exists() {
+        return Err(not_found());
+    }
+    list_archive_files(&path)
+        .change_context(REvilManagerError::UninstallError(short_name.to_string()))
+}
+
+fn get_game_executable(short_name: &str, game_config: &GameConfig) -> ResultManagerErr<PathBuf> {
+    let location = game_config
+        .location
+        .as_ref()
+        .ok_or_else(|| Report::new(REvilManagerError::GameLocationMissing))?;
+    let executable = with_game_registry(|registry| {
+        registry
+            .find_by_short_name(short_name)
+            .map(|entry| entry.executable.to_string())
+    })
+    .ok_or_else(|| Report::new(REvilManagerError::UnknownGame(short_name.to_string())))?;
+    Ok(Path::new(location).join(executable))
+}
+
+// games of the registry, limited to steamGamesIdToSearchFor when it is set
+fn get_games_to_search_for(registry: &GameRegistry, main: &Main) -> Vec<(SteamId, ShortGameName)> {
+    let to_pair = |entry: &GameEntry| (entry.steam_id.to_string(), entry.short_name.to_string());
+    match &main.steamGamesIdToSearchFor {
+        Some(steam_ids) => steam_ids
+            .iter()
+            .filter_map(|steam_id| {
+                let entry = registry.find_by_steam_id(steam_id.trim());
+                if entry.is_none() {
+                    warn!(
+                        "Steam id {} from steamGamesIdToSearchFor is not a known game, add a game section with its steamId to support it",
+                        steam_id
+                    );
+                }
+                entry.map(to_pair)
+            })
+            .collect(),
+        None => registry.games.iter().map(to_pair).collect(),
+    }
+}
+
 fn remove_game_from_update_needed_ones(req_update_games: &mut Vec<String>, game_short_name: &str) {
     match req_update_games.iter().position(|sn| sn == game_short_name) {
         Some(pos) => req_update_games.remove(pos),
@@ -1168,76 +2471,144 @@ fn remove_game_from_update_needed_ones(req_update_games: &mut Vec<String>, game_
     };
 }
 
-fn get_game_short_name_from_asset(asset: &ReleaseAsset) -> ResultManagerErr<&str> {
-    let game_short_name = asset
-        .name
-        .split_once(STANDARD_TYPE_QUALIFIER)
-        .map(|(short_name, _)| short_name)
-        .or_else(|| {
-            asset
-                .name
-                .split_once(".zip")
-                .map(|(short_name, _)| short_name)
-        })
-        .ok_or_else(|| {
-            Report::new(REvilManagerError::CannotDeductShortNameFromAssetName(
-                asset.name.clone(),
-            ))
-        })?;
-    Ok(game_short_name)
+fn get_game_short_name_from_asset(asset: &ReleaseAsset) -> ResultManagerErr<ShortGameName> {
+    get_short_name_from_asset_name(&asset.name).ok_or_else(|| {
+        Report::new(REvilManagerError::CannotDeductShortNameFromAssetName(
+            asset.name.clone(),
+        ))
+    })
 }
 
 fn add_asset_ver_to_game_conf_ver(
     game_config: &mut GameConfig,
-    version: &str,
+    game_short_name: &str,
+    release: &Release,
+    source: Option<String>,
     asset: &ReleaseAsset,
 ) {
     debug!("Adding asset {}", &asset.name);
-    let version_and_should_replace = game_config.versions.as_ref().map(|versions| {
-        let first_set = versions.first().unwrap();
-        if first_set[0] == SWITCH_IDENTIFIER {
-            if first_set.len() > 1 {
-                let vecc = [
-                    version.to_string(),
-                    asset.name.to_string(),
-                    first_set[1].to_string(),
-                ]
-                .to_vec();
-                debug!(
-                    "switch has one or more assets. Assets len {}",
-                    first_set.len() - 1
-                );
-                (vecc, true)
-            } else {
-                debug!("switch has no assets");
-                ([version.to_string(), asset.name.to_string()].to_vec(), true)
+    let mut record = VersionRecord {
+        tag: (!release.version.is_empty()).then(|| release.version.to_string()),
+        released: (!release.date.is_empty()).then(|| release.date.to_string()),
+        downloaded: Some(now_rfc3339()),
+        source,
+        ..VersionRecord::new(&release.name)
+    };
+    record.add_asset(game_short_name, &asset.name);
+    let versions = game_config.versions.get_or_insert_with(Vec::new);
+    match versions.first().and_then(|first| first.pending) {
+        Some(PendingAction::switch) => {
+            // asset of the other type comes from the same version so it stays
+            let old_record = versions.remove(0);
+            debug!("switch has {} assets", old_record.assets.len());
+            if let Some(old_asset) = old_record.assets.into_iter().next() {
+                if !record.has_asset(&old_asset.name) {
+                    record.assets.push(old_asset);
+                }
             }
-        } else if first_set[0] == UPDATE_IDENTIFIER {
-            debug!("Update identifier");
-            ([version.to_string(), asset.name.to_string()].to_vec(), true)
-        } else {
-            debug!("no switch asset {}", asset.name);
-            (
-                [version.to_string(), asset.name.to_string()].to_vec(),
-                false,
-            )
         }
+        Some(PendingAction::update) => {
+            debug!("Update pending");
+            versions.remove(0);
+        }
+        None => {
+            // i.e. when installing an older release that is already in versions keep only one record of it
+            if let Some(pos) = versions
+                .iter()
+                .position(|old_record| old_record.version == record.version)
+            {
+                let old_record = versions.remove(pos);
+                old_record.assets.into_iter().for_each(|old_asset| {
+                    if !record.has_asset(&old_asset.name) {
+                        record.assets.push(old_asset);
+                    }
+                });
+            }
+        }
+    }
+    versions.insert(0, record);
+    game_config.version_in_use = Some(release.name.to_string());
+}
+
+// reason is shown to the user as config commands print only the error itself
+fn to_config_value_error(err: Report<ConfigError>) -> Report<REvilManagerError> {
+    let reason = err.current_context().to_string();
+    err.change_context(REvilManagerError::ConfigValueError(reason))
+}
+
+// github_token from config or GITHUB_TOKEN env variable
+fn get_github_token(main: &Main) -> Option<String> {
+    main.github_token
+        .clone()
+        .or_else(|| env::var(GITHUB_TOKEN_ENV).ok())
+        .filter(|token| !token.trim().is_empty())
+}
+
+// release manager for a source, channel is set only for managers of game channels
+fn create_release_manager(
+    refr_ctor: fn(&str, &str) -> Box<dyn ManageGithub>,
+    source: &ReleaseSource,
+    main: &Main,
+    token: Option<String>,
+    channel: Option<&str>,
+) -> Box<dyn ManageGithub> {
+    let (mut manager, api_url) = match source {
+        ReleaseSource::Github {
+            owner,
+            repo,
+            api_url,
+        } => (refr_ctor(owner, repo), api_url.clone()),
+        ReleaseSource::LocalFolder(folder) => (LocalFolderRelease::from_folder(folder), None),
+    };
+    manager.set_options(GithubOptions {
+        token,
+        api_url: api_url.or_else(|| main.github_api_url.clone()),
+        asset_host: main.asset_host.clone(),
+        channel: channel.map(String::from),
     });
-    if let Some(version_and_switch) = version_and_should_replace {
-        let (version, switch) = version_and_switch;
-        let versions = game_config.versions.as_mut().unwrap();
+    manager
+}
 
-        if switch {
-            versions.remove(0);
-            versions.insert(0, version);
-        } else {
-            versions.insert(0, version);
-        }
-    } else {
-        game_config.versions =
-            Some([[version.to_string(), asset.name.to_string()].to_vec()].to_vec());
+// manager of the channel the game follows, games without a channel use the default one
+fn get_release_manager_for_game<'a>(
+    default_manager: Option<&'a dyn ManageGithub>,
+    channel_managers: &'a HashMap<String, Box<dyn ManageGithub>>,
+    game_config: Option<&GameConfig>,
+) -> Option<&'a dyn ManageGithub> {
+    match game_config.and_then(get_game_channel) {
+        Some(channel) => channel_managers
+            .get(channel)
+            .map(|manager| manager.as_ref()),
+        None => default_manager,
     }
-    game_config.version_in_use = Some(version.to_string());
+}
+
+// report of the default release where games following a channel have assets of their channel release,
+// so a game never gets assets from a channel it doesn't follow
+fn get_assets_report_for_games(
+    default_manager: Option<&dyn ManageGithub>,
+    channel_managers: &HashMap<String, Box<dyn ManageGithub>>,
+    games: &HashMap<ShortGameName, GameConfig>,
+) -> ResultManagerErr<AssetsReport> {
+    let mut report = default_manager
+        .map(|manager| manager.getAssetsReport().clone())
+        .ok_or_else(|| Report::new(REvilManagerError::ReleaseManagerIsNotInitialized))?;
+    games.iter().for_each(|(short_name, game_config)| {
+        if let Some(channel) = get_game_channel(game_config) {
+            match channel_managers
+                .get(channel)
+                .and_then(|manager| manager.getAssetsReport().get(short_name))
+            {
+                Some(assets) => {
+                    report.insert(short_name.to_string(), assets.clone());
+                }
+                None => {
+                    report.remove(short_name);
+                }
+            }
+        }
+    });
+    Ok(report)
 }
 
 fn remove_second_runtime_file(game_config: &GameConfig) -> ResultManagerErr<()> {
@@ -1286,16 +2657,30 @@ fn get_steam_id_by_short_name<'a>(
 pub mod tests {
     use crate::{
         args::ArgsClap,
+        steam::MockSteamThings,
         tests::{
             config_provider_mock::mock_conf_provider::load_from_file_default_return_mock,
-            manager_mocks::init_manager_mocks,
+            init_dialogs_mock::init_dialogs_mocks, manager_mocks::init_manager_mocks,
+            refr_github_mock::MockManageGithubM,
+        },
+        utils::{
+            fetch::RateLimit,
+            install_manifest::save_manifest,
+            local_version::{LocalGameConfig, MockLocalFiles},
         },
-        utils::local_version::LocalGameConfig,
     };
 
+    use crate::reframework_github::refr_github::AssetsReport;
+
     use super::*;
 
     fn init() -> REvilManager {
+        // tests which don't care about args still need them for the logger
+        unsafe {
+            if ARGS.is_none() {
+                ARGS = Some(ArgsClap::default());
+            }
+        }
         let (
             steam_menago,
             local_provider_mock,
@@ -1327,6 +2712,7 @@ pub mod tests {
                     level: ErrorLevel::info,
                     one: short_name.to_string(),
                     run: RunAfter::yes,
+                    ..ArgsClap::default()
                 });
             }
             let mut evil_manager = init();
@@ -1353,6 +2739,7 @@ pub mod tests {
                 level: ErrorLevel::info,
                 one: "RE8".to_string(),
                 run: RunAfter::yes,
+                ..ArgsClap::default()
             });
         }
         let mut evil_manager = init();
@@ -1368,6 +2755,618 @@ pub mod tests {
             .unwrap();
         assert_eq!(evil_manager.state.selected_assets[0].name, "RE8.zip");
     }
+    #[test]
+    fn pinned_game_is_not_updated() {
+        unsafe {
+            ARGS = Some(ArgsClap {
+                level: ErrorLevel::info,
+                one: "RE2".to_string(),
+                run: RunAfter::yes,
+                ..ArgsClap::default()
+            });
+        }
+        let mut evil_manager = init();
+        evil_manager.config.games.get_mut("RE2").unwrap().pin = Some("v1.70-rbd3145".to_string());
+        evil_manager.config.games.get_mut("RE3").unwrap().pin = Some(PIN_CURRENT.to_string());
+
+        evil_manager.set_games_that_require_update().unwrap();
+        let games_that_require_update = &evil_manager.state.games_that_require_update;
+        assert!(!games_that_require_update.contains(&"RE2".to_string()));
+        assert!(!games_that_require_update.contains(&"RE3".to_string()));
+        assert!(games_that_require_update.contains(&"RE8".to_string()));
+
+        evil_manager
+            .state
+            .games_that_require_update
+            .push("RE2".to_string());
+        evil_manager
+            .pick_one_game_from_report_and_set_as_selected()
+            .unwrap();
+        assert!(evil_manager.state.selected_assets.is_empty());
+        assert_eq!(
+            evil_manager.state.selected_game_to_launch,
+            Some("883710".to_string())
+        );
+    }
+
+    #[test]
+    fn sections_without_location_are_not_scanned_nor_updated() {
+        let (
+            steam_menago,
+            mut local_provider_mock,
+            dialogs,
+            mut config_provider_mock,
+            _ctx,
+            mock_reft_constr,
+        ) = init_manager_mocks();
+        config_provider_mock
+            .expect_load_from_file()
+            .returning(load_from_file_default_return_mock());
+        local_provider_mock
+            .expect_get_local_report_for_game()
+            .returning(|_, short_name| {
+                assert_ne!(short_name, "RE4");
+                LocalGameConfig::default()
+            });
+        let mut evil_manager = REvilManager::new(
+            config_provider_mock,
+            local_provider_mock,
+            steam_menago,
+            dialogs,
+            mock_reft_constr,
+        );
+        evil_manager.github_release_manager = Some((evil_manager.refr_ctor)("praydog", "nightly"));
+        evil_manager.load_config().unwrap();
+        evil_manager.config.games.insert(
+            "RE4".to_string(),
+            GameConfig {
+                editions: Some(vec![Edition::nextgen, Edition::standard]),
+                ..GameConfig::default()
+            },
+        );
+
+        evil_manager.get_local_settings_per_game_and_amend_current_ones();
+        evil_manager.state.selected_option = Some(LabelOptions::RescanLocal);
+        evil_manager.rescan_option().unwrap();
+        evil_manager.set_games_that_require_update().unwrap();
+        assert!(!evil_manager
+            .state
+            .games_that_require_update
+            .contains(&"RE4".to_string()));
+        assert_eq!(evil_manager.config.games["RE4"].versions, None);
+    }
+
+    #[test]
+    fn uninstall_keeps_protected_files() {
+        let mut evil_manager = init();
+        let game_dir = env::temp_dir().join("refresher_uninstall_protected");
+        if game_dir.exists() {
+            fs::remove_dir_all(&game_dir).unwrap();
+        }
+        fs::create_dir_all(game_dir.join("reframework/autorun")).unwrap();
+        fs::write(game_dir.join("dinput8.dll"), "mod").unwrap();
+        fs::write(game_dir.join("reframework/autorun/edited.lua"), "edited").unwrap();
+        fs::write(game_dir.join("re2_fw_config.txt"), "edited").unwrap();
+        let files = [
+            "dinput8.dll",
+            "reframework/autorun/edited.lua",
+            "re2_fw_config.txt",
+        ]
+        .map(String::from);
+        let short_name = "UNINSTALL_TEST";
+        save_manifest(&get_manifest_path(short_name), &files).unwrap();
+        evil_manager.config.games.insert(
+            short_name.to_string(),
+            GameConfig {
+                location: Some(game_dir.display().to_string()),
+                ..GameConfig::default()
+            },
+        );
+
+        evil_manager.uninstall_mod(short_name).unwrap();
+        assert!(!game_dir.join("dinput8.dll").exists());
+        assert!(game_dir.join("reframework/autorun/edited.lua").exists());
+        assert!(game_dir.join("re2_fw_config.txt").exists());
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+
+    #[test]
+    fn empty_versions_are_treated_as_mod_not_installed() {
+        let mut evil_manager = init();
+        let re2_config = evil_manager.config.games.get_mut("RE2").unwrap();
+        re2_config.versions = Some(Vec::new());
+        re2_config.runtime = Some(Runtime::OpenXR);
+
+        evil_manager.set_games_that_require_update().unwrap();
+        assert!(evil_manager
+            .state
+            .games_that_require_update
+            .contains(&"RE2".to_string()));
+        let err = evil_manager
+            .before_launch_procedure(&"883710".to_string())
+            .unwrap_err();
+        assert!(matches!(
+            err.current_context(),
+            REvilManagerError::ModIsNotInstalled(_)
+        ));
+    }
+
+    #[test]
+    fn select_games_to_update_uses_preferred_type() {
+        let mut evil_manager = init();
+        evil_manager.config.games.get_mut("RE3").unwrap().nextgen = None;
+        evil_manager.state.games_that_require_update =
+            ["RE3".to_string(), "RE8".to_string()].to_vec();
+
+        evil_manager
+            .select_games_to_update(
+                &["RE2".to_string(), "RE3".to_string(), "RE8".to_string()],
+                Some(true),
+            )
+            .unwrap();
+        let selected: Vec<&str> = evil_manager
+            .state
+            .selected_assets
+            .iter()
+            .map(|asset| asset.name.as_str())
+            .collect();
+        assert_eq!(selected, ["RE3.zip", "RE8.zip"]);
+    }
+
+    #[test]
+    fn load_from_cache_for_game_test() {
+        let mut evil_manager = init();
+        let err = evil_manager
+            .load_from_cache_for_game("RE2", "v1.71-abd3145")
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.current_context(),
+            &REvilManagerError::CacheNotFoundForGivenVersion("v1.71-abd3145".to_string())
+        );
+
+        evil_manager
+            .config
+            .games
+            .get_mut("RE2")
+            .unwrap()
+            .version_in_use = None;
+        evil_manager
+            .load_from_cache_for_game("RE2", "v1.70-rbd3145")
+            .unwrap();
+        let game_config = evil_manager.config.games.get("RE2").unwrap();
+        assert_eq!(
+            game_config.version_in_use,
+            Some("v1.70-rbd3145".to_string())
+        );
+        assert_eq!(game_config.nextgen, Some(true));
+    }
+
+    #[test]
+    fn manually_registered_game_is_kept_and_launched_via_executable() {
+        let mut evil_manager = init();
+        let folder = std::env::temp_dir().join("refresher_manual_game");
+        fs::create_dir_all(&folder).unwrap();
+        fs::write(folder.join("re4.exe"), "").unwrap();
+        fs::write(folder.join("re2.exe"), "").unwrap();
+        let location = folder.display().to_string();
+        let mut local_provider = Box::new(MockLocalFiles::new());
+        local_provider
+            .expect_get_local_report_for_game()
+            .returning(|_, _| LocalGameConfig {
+                version: Some("v1.0-a1b2c3d".to_string()),
+                ..LocalGameConfig::default()
+            });
+        let expected_exe = folder.join("re4.exe");
+        local_provider
+            .expect_run_game_exe()
+            .withf(move |exe, _| exe == expected_exe)
+            .once()
+            .returning(|_, _| Ok(()));
+        evil_manager.local_provider = local_provider;
+
+        let err = evil_manager.register_game("RE8", &location).err().unwrap();
+        assert!(matches!(
+            err.current_context(),
+            REvilManagerError::GameExecutableNotFound(_)
+        ));
+        let err = evil_manager
+            .register_game("UNKNOWN", &location)
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.current_context(),
+            &REvilManagerError::UnknownGame("UNKNOWN".to_string())
+        );
+        evil_manager
+            .register_game("RE4", &location)
+            .and_then(|this| this.register_game("RE2", &location))
+            .and_then(|this| this.load_games_from_steam())
+            .unwrap();
+        let re4 = &evil_manager.config.games["RE4"];
+        assert_eq!(re4.steamId, Some("2050650".to_string()));
+        assert_eq!(re4.version_in_use, Some("v1.0-a1b2c3d".to_string()));
+        // Steam detection found RE2 too but the manual location wins
+        assert_eq!(
+            evil_manager.config.games["RE2"].location,
+            Some(location.to_string())
+        );
+
+        evil_manager
+            .select_game_to_launch("RE4")
+            .and_then(|this| this.launch_game())
+            .unwrap();
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    fn self_update_release_ctor(owner: &str, repo: &str) -> Box<dyn ManageGithub> {
+        assert_eq!((owner, repo), (SELF_REPO_OWNER, SELF_REPO_NAME));
+        let mut mock = MockManageGithubM::new();
+        mock.expect_set_options().return_const(());
+        mock.expect_set_release().returning(|_| Ok(()));
+        mock.expect_get_reframework_releases().returning(|_, _| {
+            Ok(["99.0.0", "0.1.0"]
+                .map(|version| Release {
+                    version: version.to_string(),
+                    assets: vec![ReleaseAsset {
+                        name: format!("REFresher-{}", self_update::get_target()),
+                        download_url: "url".to_string(),
+                    }],
+                    ..Default::default()
+                })
+                .to_vec())
+        });
+        Box::new(mock)
+    }
+
+    #[test]
+    fn newer_refresher_is_offered_when_autoupdate_is_off() {
+        let mut evil_manager = init();
+        evil_manager.config.main.autoupdate = Some(false);
+        evil_manager.refr_ctor = self_update_release_ctor;
+        evil_manager.check_for_self_update().unwrap();
+        let release = evil_manager.state.self_update_release.as_ref().unwrap();
+        assert_eq!(release.version, "99.0.0");
+        assert!(evil_manager.self_release_manager.is_some());
+
+        let label = LabelOptions::SelfUpdate(release.version.clone()).to_label();
+        assert_eq!(
+            LabelOptions::from(&label[..]),
+            LabelOptions::SelfUpdate("99.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn run_args_and_game_args_are_passed_to_steam() {
+        unsafe {
+            ARGS = Some(ArgsClap {
+                level: ErrorLevel::info,
+                one: "RE2".to_string(),
+                game_args: Some("-nosplash".to_string()),
+                ..ArgsClap::default()
+            });
+        }
+        let mut evil_manager = init();
+        evil_manager.config.games.get_mut("RE2").unwrap().runArgs =
+            Some("-dx11 -config \"my cfg.ini\"".to_string());
+        let mut steam_menago = Box::new(MockSteamThings::new());
+        steam_menago
+            .expect_run_game_via_steam_manager()
+            .withf(|id, args| {
+                assert_eq!(id, "883710");
+                assert_eq!(args, ["-dx11", "-config", "my cfg.ini", "-nosplash"]);
+                true
+            })
+            .once()
+            .returning(|_, _| Ok(()));
+        evil_manager.steam_menago = steam_menago;
+        evil_manager
+            .select_game_to_launch("RE2")
+            .and_then(|this| this.launch_game())
+            .unwrap();
+    }
+
+    #[test]
+    fn shortcuts_are_regenerated_when_executable_moved() {
+        let mut evil_manager = init();
+        let folder = std::env::temp_dir().join("refresher_shortcuts_regenerate");
+        fs::create_dir_all(&folder).unwrap();
+        evil_manager
+            .config
+            .games
+            .iter()
+            .for_each(|(short_name, _)| {
+                fs::write(folder.join(get_shortcut_file_name(short_name)), "").unwrap();
+            });
+        save_shortcuts_target(&folder, Path::new("/old/place/REFresher")).unwrap();
+        let games_count = evil_manager.config.games.len();
+        let mut local_provider = Box::new(MockLocalFiles::new());
+        let shortcuts_folder = folder.clone();
+        local_provider
+            .expect_create_cache_dir()
+            .returning(move || Ok(shortcuts_folder.clone()));
+        local_provider
+            .expect_create_shortcut()
+            .times(games_count)
+            .returning(|_, _, _| Ok(()));
+        evil_manager.local_provider = local_provider;
+
+        evil_manager.generate_shortcuts().unwrap();
+        // second run finds shortcuts pointing to current executable
+        evil_manager.generate_shortcuts().unwrap();
+        assert_eq!(
+            read_shortcuts_target(&folder),
+            Some(env::current_exe().unwrap())
+        );
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn switch_type_for_game_requires_supported_game() {
+        let mut evil_manager = init();
+        let err = evil_manager.switch_type_for_game("RE8").err().unwrap();
+        assert_eq!(
+            err.current_context(),
+            &REvilManagerError::SwitchTypeNotSupported("RE8".to_string())
+        );
+        // RE2 has older version in use than the latest cached one
+        let err = evil_manager.switch_type_for_game("RE2").err().unwrap();
+        assert_eq!(
+            err.current_context(),
+            &REvilManagerError::SwitchTypeNotSupported("RE2".to_string())
+        );
+    }
+
+    #[test]
+    fn download_update_removes_only_failed_assets() {
+        let mut evil_manager = init();
+        evil_manager.config.main.max_parallel_downloads = Some(2);
+        let mut mock = MockManageGithubM::new();
+        let release: &'static Release = Box::leak(Box::new(Release {
+            name: "concurrent_download_test".to_string(),
+            ..Default::default()
+        }));
+        mock.expect_getRelease().return_const(Some(release));
+        mock.expect_verify_release_asset().returning(|_| Ok(()));
+        mock.expect_source_name()
+            .return_const("praydog/REFramework-nightly".to_string());
+        // failed one is retried
+        mock.expect_download_release_asset()
+            .times(4)
+            .returning(|asset, _| {
+                if asset.name == "RE3.zip" {
+                    Err("connection reset".into())
+                } else {
+                    Ok(())
+                }
+            });
+        evil_manager.github_release_manager = Some(Box::new(mock));
+        evil_manager.state.selected_assets = ["RE2.zip", "RE3.zip", "RE8.zip"]
+            .map(|name| ReleaseAsset {
+                name: name.to_string(),
+                download_url: "".to_string(),
+            })
+            .to_vec();
+
+        evil_manager.download_REFramework_update().unwrap();
+        let selected: Vec<&str> = evil_manager
+            .state
+            .selected_assets
+            .iter()
+            .map(|asset| asset.name.as_str())
+            .collect();
+        assert_eq!(selected, ["RE2.zip", "RE8.zip"]);
+    }
+
+    #[test]
+    fn next_release_source_is_used_when_one_fails() {
+        let mut evil_manager = init();
+        // constructor context has to live as long as the manager creates release managers
+        let (.., _ctx, mock_reft_constr) = init_manager_mocks();
+        evil_manager.refr_ctor = mock_reft_constr;
+        evil_manager.config.main.sources = Some(vec![
+            "local:refr_missing_release_folder".to_string(),
+            "nightly".to_string(),
+        ]);
+        evil_manager.check_for_REFramework_update().unwrap();
+        assert_eq!(
+            evil_manager
+                .github_release_manager
+                .as_ref()
+                .unwrap()
+                .source_name(),
+            "praydog/REFramework-nightly"
+        );
+
+        evil_manager.config.main.sources =
+            Some(vec!["local:refr_missing_release_folder".to_string()]);
+        assert!(evil_manager.check_for_REFramework_update().is_err());
+        assert!(evil_manager.github_release_manager.is_none());
+    }
+
+    #[test]
+    fn game_channel_gets_assets_from_its_channel_release() {
+        let mut evil_manager = init();
+        let (.., _ctx, mock_reft_constr) = init_manager_mocks();
+        evil_manager.refr_ctor = mock_reft_constr;
+        evil_manager.config.games.get_mut("RE8").unwrap().channel = Some("stable".to_string());
+        evil_manager.check_for_REFramework_update().unwrap();
+        assert_eq!(
+            evil_manager
+                .channel_release_managers
+                .keys()
+                .collect::<Vec<_>>(),
+            ["stable"]
+        );
+
+        let mut stable_report = AssetsReport::new();
+        ["RE2", "RE8"].iter().for_each(|short_name| {
+            stable_report.insert(
+                short_name.to_string(),
+                vec![ReleaseAsset {
+                    name: format!("{}.zip", short_name),
+                    download_url: "stable_url".to_string(),
+                }],
+            );
+        });
+        let mut mock = MockManageGithubM::new();
+        mock.expect_getAssetsReport()
+            .return_const(Box::leak(Box::new(stable_report)) as &AssetsReport);
+        evil_manager
+            .channel_release_managers
+            .insert("stable".to_string(), Box::new(mock));
+
+        let report = get_assets_report_for_games(
+            evil_manager.github_release_manager.as_deref(),
+            &evil_manager.channel_release_managers,
+            &evil_manager.config.games,
+        )
+        .unwrap();
+        assert_eq!(report["RE8"][0].download_url, "stable_url");
+        assert_ne!(report["RE2"][0].download_url, "stable_url");
+
+        evil_manager.channel_release_managers.clear();
+        let report = get_assets_report_for_games(
+            evil_manager.github_release_manager.as_deref(),
+            &evil_manager.channel_release_managers,
+            &evil_manager.config.games,
+        )
+        .unwrap();
+        assert!(!report.contains_key("RE8"));
+    }
+
+    #[test]
+    fn downloaded_version_is_recorded_with_its_release_info() {
+        let mut game_config = GameConfig {
+            versions: Some(vec![
+                VersionRecord {
+                    pending: Some(PendingAction::switch),
+                    ..VersionRecord::from_assets("RE2", "v2", &["RE2.zip"])
+                },
+                VersionRecord::from_assets("RE2", "v1", &["RE2.zip"]),
+            ]),
+            ..GameConfig::default()
+        };
+        let release = Release {
+            name: "v2".to_string(),
+            version: "2".to_string(),
+            date: "2022-07-20T18:03:55Z".to_string(),
+            ..Default::default()
+        };
+        let asset = ReleaseAsset {
+            name: "RE2_TDB.zip".to_string(),
+            download_url: "url".to_string(),
+        };
+        add_asset_ver_to_game_conf_ver(
+            &mut game_config,
+            "RE2",
+            &release,
+            Some("praydog/REFramework-nightly".to_string()),
+            &asset,
+        );
+        let versions = game_config.versions.as_ref().unwrap();
+        assert_eq!(versions.len(), 2);
+        let record = &versions[0];
+        assert_eq!(record.version, "v2");
+        assert_eq!(record.tag.as_deref(), Some("2"));
+        assert_eq!(record.released.as_deref(), Some("2022-07-20T18:03:55Z"));
+        assert!(record.downloaded.is_some());
+        assert_eq!(
+            record.source.as_deref(),
+            Some("praydog/REFramework-nightly")
+        );
+        assert_eq!(record.pending, None);
+        // switched from Nextgen so both editions are cached now
+        assert_eq!(
+            record
+                .assets
+                .iter()
+                .map(|asset| (asset.name.as_str(), asset.edition))
+                .collect::<Vec<_>>(),
+            vec![
+                ("RE2_TDB.zip", Some(Edition::standard)),
+                ("RE2.zip", Some(Edition::nextgen))
+            ]
+        );
+        assert_eq!(game_config.version_in_use.as_deref(), Some("v2"));
+    }
+
+    #[test]
+    fn rate_limit_gets_own_error() {
+        let rate_limit = RateLimit {
+            limit: Some(60),
+            remaining: Some(0),
+            reset: Some(1690000000),
+        };
+        let err = to_checking_release_err(Box::new(REFRGithubError::RateLimitExceeded(
+            rate_limit.clone(),
+        )));
+        assert_eq!(
+            err.current_context(),
+            &REvilManagerError::GithubRateLimitExceeded(rate_limit.to_string())
+        );
+        let err = to_checking_release_err("No releases found".into());
+        assert_eq!(
+            err.current_context(),
+            &REvilManagerError::CheckingNewReleaseErr
+        );
+    }
+
+    #[test]
+    fn install_release_from_args_test() {
+        unsafe {
+            ARGS = Some(ArgsClap {
+                level: ErrorLevel::info,
+                one: "RE8".to_string(),
+                run: RunAfter::no,
+                release: Some("previous".to_string()),
+                ..ArgsClap::default()
+            });
+        }
+        let mut evil_manager = init();
+        let mut mock = MockManageGithubM::new();
+        // set_release is mocked so getRelease always returns the release picked by the selector
+        let picked: &'static Release = Box::leak(Box::new(Release {
+            name: "v1.332-17ab146".to_string(),
+            ..Default::default()
+        }));
+        mock.expect_getRelease().return_const(Some(picked));
+        mock.expect_get_reframework_releases().returning(|_, _| {
+            Ok(["v1.333-07ab146", "v1.332-17ab146", "v1.331-27ab146"]
+                .map(|name| Release {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .to_vec())
+        });
+        mock.expect_set_release()
+            .withf(|release| release.as_ref().unwrap().name == "v1.332-17ab146")
+            .times(2)
+            .returning(|_| Ok(()));
+        mock.expect_getAssetsReport()
+            .return_const(Box::leak(Box::new(init_dialogs_mocks().0)) as &AssetsReport);
+        mock.expect_verify_release_asset().returning(|_| Ok(()));
+        mock.expect_source_name()
+            .return_const("praydog/REFramework-nightly".to_string());
+        mock.expect_download_release_asset()
+            .withf(|asset, _| asset.name == "RE8.zip")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        evil_manager.github_release_manager = Some(Box::new(mock));
+
+        evil_manager.install_release_from_args().unwrap();
+        assert!(evil_manager.state.selected_assets.is_empty());
+        assert!(evil_manager.state.selected_game_to_launch.is_none());
+        let re8_config = evil_manager.config.games.get("RE8").unwrap();
+        assert_eq!(
+            re8_config.version_in_use,
+            Some("v1.332-17ab146".to_string())
+        );
+        let record = re8_config.versions.as_ref().unwrap().first().unwrap();
+        assert_eq!(record.version, "v1.332-17ab146");
+        assert!(record.has_asset("RE8.zip"));
+    }
+
     #[test]
     fn reset_option_test() {
         unsafe {
@@ -1375,6 +3374,7 @@ pub mod tests {
                 level: ErrorLevel::info,
                 one: "none".to_string(),
                 run: RunAfter::yes,
+                ..ArgsClap::default()
             });
         }
         let (
@@ -1428,9 +3428,8 @@ pub mod tests {
                 .unwrap()
                 .first()
                 .unwrap()
-                .first()
-                .unwrap(),
-            &"v1.71-abd3145".to_string()
+                .version,
+            "v1.71-abd3145"
         );
         let re2_config = evil_manager.config.games.get("RE2").unwrap();
         assert_eq!(re2_config.version_in_use, Some("1234567".to_string()));
@@ -1444,9 +3443,8 @@ pub mod tests {
                 .unwrap()
                 .first()
                 .unwrap()
-                .first()
-                .unwrap(),
-            &"1234567".to_string()
+                .version,
+            "1234567"
         );
         let re8_config = evil_manager.config.games.get("RE8").unwrap();
         assert_eq!(re8_config.version_in_use, Some("some123".to_string()));
@@ -1459,9 +3457,8 @@ pub mod tests {
                 .unwrap()
                 .first()
                 .unwrap()
-                .first()
-                .unwrap(),
-            &"v1.71-abd3145".to_string()
+                .version,
+            "v1.71-abd3145"
         )
     }
 }
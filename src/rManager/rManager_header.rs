@@ -1,23 +1,23 @@
 use std::{
+    collections::HashMap,
     error::Error,
     ffi::OsStr,
     fmt::{self},
 };
 
 use log::Level;
-use self_update::update::ReleaseAsset;
+use self_update::update::{Release, ReleaseAsset};
 
 use crate::{
-    args::RunAfter,
+    args::{RunAfter, StatusFormat},
     dialogs::{dialogs::Ask, dialogs_label::LabelOptions},
     refr_github::ManageGithub,
     steam::SteamThings,
     tomlConf::{
         config::ConfigProvider,
-        configStruct::{REvilConfig, ShortGameName, SteamId},
+        configStruct::{REvilConfig, Runtime, ShortGameName, SteamId},
     },
     utils::local_version::LocalFiles,
-    DynResult,
 };
 use error_stack::Result;
 
@@ -31,6 +31,10 @@ pub struct REvilManager {
     pub local_provider: Box<dyn LocalFiles>,
     pub dialogs: Box<dyn Ask>,
     pub github_release_manager: Option<Box<dyn ManageGithub>>,
+    // managers of release channels set per game, by channel name
+    pub channel_release_managers: HashMap<String, Box<dyn ManageGithub>>,
+    // manager of REFresher releases, set by check_for_self_update when a newer release is found
+    pub self_release_manager: Option<Box<dyn ManageGithub>>,
     pub refr_ctor: fn(&str, &str) -> Box<dyn ManageGithub>,
     pub state: REvilManagerState,
 }
@@ -47,7 +51,7 @@ pub trait REvilThings {
     fn load_games_from_steam(&mut self) -> ResultManagerErr<&mut Self>;
     fn generate_main_defaults(&mut self) -> Result<&mut Self, REvilManagerError>;
     fn get_local_settings_per_game_and_amend_current_ones(&mut self) -> &mut Self;
-    fn generate_ms_links(&mut self) -> Result<&mut Self, REvilManagerError>;
+    fn generate_shortcuts(&mut self) -> Result<&mut Self, REvilManagerError>;
     fn decision_loop(&mut self) -> ResultManagerErr<&mut Self>;
     fn check_for_REFramework_update(&mut self) -> ResultManagerErr<&mut Self>;
     fn pick_one_game_from_report_and_set_as_selected(&mut self) -> ResultManagerErr<&mut Self>;
@@ -73,8 +77,39 @@ pub trait REvilThings {
     fn ask_for_switch_runtime_if_needed(&mut self) -> ResultManagerErr<&mut Self>;
     fn ask_for_switch_type_decision(&mut self, run_after: RunAfter) -> ResultManagerErr<&mut Self>;
     fn load_from_cache_if_chosen(&mut self) -> ResultManagerErr<&mut Self>;
-    fn check_for_self_update(&mut self) -> DynResult<&mut Self>;
-    fn self_update(&mut self) -> DynResult<&mut Self>;
+    fn install_release_if_chosen(&mut self) -> ResultManagerErr<&mut Self>;
+    fn install_release_from_args(&mut self) -> ResultManagerErr<&mut Self>;
+    fn pin_version_if_chosen(&mut self) -> ResultManagerErr<&mut Self>;
+    fn uninstall_if_chosen(&mut self) -> ResultManagerErr<&mut Self>;
+    fn select_games_to_update(
+        &mut self,
+        games: &[ShortGameName],
+        prefer_nextgen: Option<bool>,
+    ) -> ResultManagerErr<&mut Self>;
+    fn switch_type_for_game(&mut self, short_name: &str) -> ResultManagerErr<&mut Self>;
+    fn switch_runtime_for_game(
+        &mut self,
+        short_name: &str,
+        runtime: Runtime,
+    ) -> ResultManagerErr<&mut Self>;
+    fn load_from_cache_for_game(
+        &mut self,
+        short_name: &str,
+        version: &str,
+    ) -> ResultManagerErr<&mut Self>;
+    fn select_game_to_launch(&mut self, short_name: &str) -> ResultManagerErr<&mut Self>;
+    fn register_game(&mut self, short_name: &str, location: &str) -> ResultManagerErr<&mut Self>;
+    fn register_game_if_chosen(&mut self) -> ResultManagerErr<&mut Self>;
+    fn print_status(&mut self, format: &StatusFormat) -> ResultManagerErr<&mut Self>;
+    fn check_config(&mut self) -> ResultManagerErr<&mut Self>;
+    fn restore_config(&mut self, number: u8) -> ResultManagerErr<&mut Self>;
+    fn prune_cache(&mut self, dry_run: bool) -> ResultManagerErr<&mut Self>;
+    fn get_config_value(&mut self, key: &str) -> ResultManagerErr<&mut Self>;
+    fn set_config_value(&mut self, key: &str, value: &str) -> ResultManagerErr<&mut Self>;
+    fn check_for_self_update(&mut self) -> ResultManagerErr<&mut Self>;
+    fn self_update(&mut self) -> ResultManagerErr<&mut Self>;
+    fn self_update_if_chosen(&mut self) -> ResultManagerErr<&mut Self>;
+    fn rollback_self_update(&mut self) -> ResultManagerErr<&mut Self>;
     fn before_launch_procedure(&self, steam_id: &String) -> ResultManagerErr<String>;
     fn launch_game(&mut self) -> ResultManagerErr<&mut Self>;
     fn bind(
@@ -93,13 +128,17 @@ pub trait REvilThings {
 pub enum REvilManagerError {
     ReleaseIsEmpty,
     CheckingNewReleaseErr,
+    GithubRateLimitExceeded(String),
+    ReleaseNotFound(String),
     GameNotFoundForGivenShortName(String),
     GameNotFoundForGivenSteamId(String),
+    UnknownGame(String),
+    GameExecutableNotFound(String),
     CannotDeductShortNameFromAssetName(String),
     RemoveFileFailed(String),
     RemoveZipAssetFromCacheErr(String),
     CacheNotFoundForGivenVersion(String),
-    FailedToCreateMsLink(String),
+    FailedToCreateShortcut(String),
     ReleaseManagerIsNotInitialized,
     GameLocationMissing,
     NoGamesToUpdate,
@@ -107,10 +146,18 @@ pub enum REvilManagerError {
     GetLocalPathToCacheErr,
     UnzipError(String),
     DownloadAssetError(String),
+    AssetVerificationFailed(String),
     ModIsNotInstalled(String),
+    InstallManifestNotFound(String),
+    SwitchTypeNotSupported(String),
+    UninstallError(String),
     ErrorRestartingProgram,
+    SelfUpdateError(String),
     SaveConfigError,
     LoadConfigError,
+    InvalidConfig(usize),
+    RestoreConfigError(u8),
+    ConfigValueError(String),
     #[default]
     Other,
 }
@@ -120,6 +167,10 @@ impl fmt::Display for REvilManagerError {
         match self {
             REvilManagerError::ReleaseIsEmpty => write!(f, "ReleaseIsEmpty"),
             REvilManagerError::CheckingNewReleaseErr => write!(f, "CheckingNewReleaseErr"),
+            REvilManagerError::GithubRateLimitExceeded(info) => write!(f, "{}", info),
+            REvilManagerError::ReleaseNotFound(selector) => {
+                write!(f, "ReleaseNotFound {}", selector)
+            }
             REvilManagerError::GameNotFoundForGivenShortName(info) => {
                 write!(f, "GameNotFoundForGivenShortName {}", info)
             }
@@ -136,6 +187,13 @@ impl fmt::Display for REvilManagerError {
             REvilManagerError::UnzipError(more) => write!(f, "UnzipError {}", more),
             REvilManagerError::SaveConfigError => write!(f, "SaveConfigError"),
             REvilManagerError::LoadConfigError => write!(f, "LoadConfigError"),
+            REvilManagerError::InvalidConfig(problems) => {
+                write!(f, "Config file has {} problem(s)", problems)
+            }
+            REvilManagerError::RestoreConfigError(number) => {
+                write!(f, "Restoring config from backup {} failed", number)
+            }
+            REvilManagerError::ConfigValueError(info) => write!(f, "{}", info),
             REvilManagerError::Other => write!(f, "Other"),
             REvilManagerError::RemoveFileFailed(info) => write!(f, "RemoveFileFiled {}", info),
             REvilManagerError::GameNotFoundForGivenSteamId(info) => {
@@ -144,12 +202,15 @@ impl fmt::Display for REvilManagerError {
             REvilManagerError::CacheNotFoundForGivenVersion(info) => {
                 write!(f, "CacheNotFoundForGivenVersion {}", info)
             }
-            REvilManagerError::FailedToCreateMsLink(info) => {
-                write!(f, "FailedToCreateMsLink {}", info)
+            REvilManagerError::FailedToCreateShortcut(info) => {
+                write!(f, "FailedToCreateShortcut {}", info)
             }
             REvilManagerError::GetLocalPathToCacheErr => write!(f, "GetLocalPathToCacheErr"),
             REvilManagerError::ModRuntimeIsNone(game) => write!(f, "ModRuntimeIsNone for {}", game),
             REvilManagerError::ErrorRestartingProgram => write!(f, "ErrorRestartingProgram"),
+            REvilManagerError::SelfUpdateError(info) => {
+                write!(f, "REFresher update failed {}", info)
+            }
             REvilManagerError::DownloadAssetError(asset_name) => {
                 write!(
                     f,
@@ -157,10 +218,36 @@ impl fmt::Display for REvilManagerError {
                     asset_name
                 )
             }
+            REvilManagerError::AssetVerificationFailed(asset_name) => {
+                write!(
+                    f,
+                    "Downloaded {} asset is broken and has not been installed",
+                    asset_name
+                )
+            }
             REvilManagerError::ModIsNotInstalled(short_name) => {
                 write!(f, "Mod is not installed for {}", short_name)
             }
+            REvilManagerError::InstallManifestNotFound(short_name) => {
+                write!(
+                    f,
+                    "Not found list of installed mod files for {}. Nothing to uninstall",
+                    short_name
+                )
+            }
+            REvilManagerError::SwitchTypeNotSupported(short_name) => {
+                write!(f, "Switching mod type is not possible for {}", short_name)
+            }
+            REvilManagerError::UninstallError(short_name) => {
+                write!(f, "Uninstall of mod for {} failed", short_name)
+            }
             REvilManagerError::NoGamesToUpdate => write!(f, "No games to update"),
+            REvilManagerError::UnknownGame(short_name) => {
+                write!(f, "{} is not a known game", short_name)
+            }
+            REvilManagerError::GameExecutableNotFound(path) => {
+                write!(f, "Game executable not found at {}", path)
+            }
         }
     }
 }
@@ -175,4 +262,6 @@ pub struct REvilManagerState {
     pub config_loading_error_ocurred: bool,
     pub new_steam_game_found: bool,
     pub selected_option: Option<LabelOptions>,
+    // newer REFresher release found by check_for_self_update
+    pub self_update_release: Option<Release>,
 }
@@ -0,0 +1,241 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use indicatif::ProgressBar;
+use log::{debug, trace};
+use self_update::update::{Release, ReleaseAsset};
+
+use crate::{
+    reframework_github::{
+        refr_github::{generate_assets_report_from, AssetsReport, GithubOptions, ManageGithub},
+        release::AssetsInfo,
+        release_source::LOCAL_SOURCE_PREFIX,
+    },
+    unzip::unzip::verify_archive,
+    utils::get_local_path_to_cache::{get_local_path_to_cache_folder, get_partial_download_path},
+    DynResult,
+};
+
+// releases kept on disk, one folder per version with assets inside i.e. <folder>/v1.460-a1b2c3d/RE2.zip.
+// Newest modified version folder is the latest release
+#[derive(Clone, Debug, Default)]
+pub struct LocalFolderRelease {
+    folder: PathBuf,
+    channel: Option<String>,
+    pub release: Option<Release>,
+    pub report: AssetsReport,
+}
+
+impl LocalFolderRelease {
+    pub fn from_folder(folder: &Path) -> Box<dyn ManageGithub> {
+        Box::new(LocalFolderRelease {
+            folder: folder.to_path_buf(),
+            ..LocalFolderRelease::default()
+        })
+    }
+
+    fn read_releases(&self) -> DynResult<Vec<Release>> {
+        let entries = fs::read_dir(&self.folder).map_err(|err| {
+            format!(
+                "Can't read release folder {} Err {}",
+                self.folder.display(),
+                err
+            )
+        })?;
+        let mut releases: Vec<(SystemTime, Release)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_dir())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let modified = entry.metadata().and_then(|it| it.modified()).ok()?;
+                let mut assets: Vec<ReleaseAsset> = fs::read_dir(entry.path())
+                    .ok()?
+                    .filter_map(|file| file.ok())
+                    .map(|file| file.path())
+                    .filter(|path| {
+                        path.is_file()
+                            && path
+                                .extension()
+                                .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+                    })
+                    .map(|path| ReleaseAsset {
+                        name: path.file_name().unwrap().to_string_lossy().to_string(),
+                        download_url: path.to_string_lossy().to_string(),
+                    })
+                    .collect();
+                if assets.is_empty() {
+                    return None;
+                }
+                assets.sort_by(|a, b| a.name.cmp(&b.name));
+                let date = modified
+                    .duration_since(UNIX_EPOCH)
+                    .map(|it| it.as_secs())
+                    .unwrap_or_default();
+                let release = Release {
+                    version: name.trim_start_matches('v').to_string(),
+                    name,
+                    date: date.to_string(),
+                    body: None,
+                    assets,
+                };
+                Some((modified, release))
+            })
+            .collect();
+        releases.sort_by(|(a_time, a), (b_time, b)| {
+            b_time.cmp(a_time).then_with(|| b.name.cmp(&a.name))
+        });
+        Ok(releases.into_iter().map(|(_, release)| release).collect())
+    }
+}
+
+impl ManageGithub for LocalFolderRelease {
+    fn get_reframework_latest_release(&mut self) -> DynResult<()> {
+        let (release, _) = self.fetch_release()?;
+        trace!("{:?}", release);
+        self.release = Some(release);
+        self.generate_assets_report()?;
+        trace!("Assets Report: {:#?}", self.report);
+        Ok(())
+    }
+
+    fn generate_assets_report(&mut self) -> DynResult<()> {
+        let assets = &self.release.as_ref().ok_or("Release not found")?.assets;
+        self.report = generate_assets_report_from(assets)?;
+        Ok(())
+    }
+
+    // copies asset into cache the same way as download does, so interrupted copy is never taken as cached one
+    fn download_release_asset(
+        &self,
+        release_asset: &ReleaseAsset,
+        progress: &ProgressBar,
+    ) -> DynResult<()> {
+        let folders =
+            get_local_path_to_cache_folder(self.release.as_ref(), None, self.channel.as_deref())?;
+        fs::create_dir_all(&folders).map_err(|err| {
+            format!(
+                "Error during create_dir_all path {} Err {}",
+                folders.display(),
+                err
+            )
+        })?;
+        let path = &folders.join(&release_asset.name);
+        let part_path = get_partial_download_path(path);
+        debug!(
+            "Copying {} to {}",
+            release_asset.download_url,
+            part_path.display()
+        );
+        progress.set_message(format!("Copying {}", release_asset.name));
+        let source = fs::File::open(&release_asset.download_url).map_err(|err| {
+            format!(
+                "Error during opening file. path {} Err {}",
+                release_asset.download_url, err
+            )
+        })?;
+        progress.set_length(source.metadata()?.len());
+        let mut tmp_archive = fs::File::create(&part_path)?;
+        io::copy(&mut progress.wrap_read(source), &mut tmp_archive)?;
+        drop(tmp_archive);
+        fs::rename(&part_path, path)?;
+        Ok(())
+    }
+
+    fn verify_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<()> {
+        let path =
+            get_local_path_to_cache_folder(self.release.as_ref(), None, self.channel.as_deref())?
+                .join(&release_asset.name);
+        verify_archive(&path).map_err(|err| format!("{:?}", err))?;
+        debug!("Asset {} verified", path.display());
+        Ok(())
+    }
+
+    fn fetch_release(&self) -> DynResult<(Release, AssetsInfo)> {
+        let release = self
+            .read_releases()?
+            .into_iter()
+            .next()
+            .ok_or(format!("No release found in {}", self.folder.display()))?;
+        Ok((release, AssetsInfo::new()))
+    }
+
+    fn get_reframework_releases(&mut self, page: u32, per_page: u32) -> DynResult<Vec<Release>> {
+        let skip = page.saturating_sub(1) as usize * per_page as usize;
+        Ok(self
+            .read_releases()?
+            .into_iter()
+            .skip(skip)
+            .take(per_page as usize)
+            .collect())
+    }
+
+    fn set_release(&mut self, release: Option<Release>) -> DynResult<()> {
+        self.release = release;
+        if self.release.is_some() {
+            self.generate_assets_report()?;
+        } else {
+            self.report.clear();
+        }
+        Ok(())
+    }
+
+    // there is nothing to authorize or redirect for files on disk, only the channel matters for the cache
+    fn set_options(&mut self, options: GithubOptions) {
+        self.channel = options.channel;
+    }
+
+    fn source_name(&self) -> String {
+        format!("{}{}", LOCAL_SOURCE_PREFIX, self.folder.display())
+    }
+
+    fn getRelease(&self) -> Option<&Release> {
+        self.release.as_ref()
+    }
+
+    fn getAssetsReport(&self) -> &AssetsReport {
+        &self.report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::http_stand_in::local_server::zip_with_files;
+
+    #[test]
+    fn latest_local_release_is_copied_to_cache() {
+        let folder = std::env::temp_dir().join("refresher_local_source");
+        if folder.exists() {
+            fs::remove_dir_all(&folder).unwrap();
+        }
+        fs::create_dir_all(folder.join("v1.0-old")).unwrap();
+        fs::create_dir_all(folder.join("v9.998-local")).unwrap();
+        fs::create_dir_all(folder.join("empty")).unwrap();
+        let zip = zip_with_files(&["dinput8.dll"]);
+        fs::write(folder.join("v1.0-old/RE2.zip"), &zip).unwrap();
+        fs::write(folder.join("v9.998-local/RE2.zip"), &zip).unwrap();
+        fs::write(folder.join("v9.998-local/RE8.zip"), &zip).unwrap();
+        fs::write(folder.join("v9.998-local/notes.txt"), "x").unwrap();
+
+        let mut manager = LocalFolderRelease::from_folder(&folder);
+        manager.get_reframework_latest_release().unwrap();
+        assert_eq!(manager.getRelease().unwrap().name, "v9.998-local");
+        assert_eq!(manager.getAssetsReport().len(), 2);
+        assert_eq!(manager.get_reframework_releases(1, 10).unwrap().len(), 2);
+
+        let asset = &manager.getAssetsReport()["RE8"][0];
+        manager
+            .download_release_asset(asset, &ProgressBar::hidden())
+            .unwrap();
+        manager.verify_release_asset(asset).unwrap();
+        let cache_folder =
+            get_local_path_to_cache_folder(None, Some("v9.998-local"), None).unwrap();
+        assert!(cache_folder.join("RE8.zip").is_file());
+
+        fs::remove_dir_all(&cache_folder).unwrap();
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
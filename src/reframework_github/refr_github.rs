@@ -6,19 +6,28 @@ use std::{
     collections::HashMap,
     error::Error,
     fmt::{Display, Formatter},
-    fs,
+    fs, io,
+    path::Path,
+    time::Duration,
 };
 
-use log::{info, trace};
-use reqwest::header;
-use self_update::{
-    update::{Release, ReleaseAsset},
-    Download,
-};
+use indicatif::ProgressBar;
+use log::{debug, trace};
+use reqwest::{header, StatusCode, Url};
+use self_update::update::{Release, ReleaseAsset};
+use sha2::{Digest, Sha256};
 
 use crate::{
-    utils::{fetch::fetch_release_api, get_local_path_to_cache::get_local_path_to_cache_folder},
-    DynResult, GAMES_NEXTGEN_SUPPORT,
+    reframework_github::release::AssetsInfo,
+    unzip::unzip::verify_archive,
+    utils::{
+        fetch::{
+            check_response_status, fetch_release_api, fetch_releases_api, github_headers, RateLimit,
+        },
+        game_registry::with_game_registry,
+        get_local_path_to_cache::{get_local_path_to_cache_folder, get_partial_download_path},
+    },
+    DynResult,
 };
 
 pub type GameShortName = String;
@@ -31,35 +40,70 @@ pub struct REFRGithub {
     repo_owner: String,
     pub release: Option<Release>,
     pub report: AssetsReport,
+    pub assets_info: AssetsInfo,
+    options: GithubOptions,
+}
+
+pub static GITHUB_API_URL: &str = "https://api.github.com";
+
+// a stalled download fails instead of hanging, the next attempt resumes it from .part file
+const DOWNLOAD_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+const DOWNLOAD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+// token and hosts used for GitHub requests. api_url allows GitHub Enterprise or a mirror,
+// asset_host replaces scheme and host of asset download urls i.e. for a proxy re-hosting release files
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GithubOptions {
+    pub token: Option<String>,
+    pub api_url: Option<String>,
+    pub asset_host: Option<String>,
+    // release channel the manager was created for, its assets are cached separately from the default one
+    pub channel: Option<String>,
 }
 
 #[derive(Debug)]
 pub enum REFRGithubError {
     VersionIsNoneAndReleaseIsNone,
+    RateLimitExceeded(RateLimit),
 }
 
 impl Display for REFRGithubError {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
-        fmt.write_str("REFRGithub Error")
+        match self {
+            REFRGithubError::RateLimitExceeded(rate_limit) => write!(fmt, "{}", rate_limit),
+            _ => fmt.write_str("REFRGithub Error"),
+        }
     }
 }
 
 impl Error for REFRGithubError {}
 
-pub trait ManageGithub<T = REFRGithub> {
+// Sync as assets are downloaded concurrently by the same manager
+pub trait ManageGithub<T = REFRGithub>: Sync {
     fn get_reframework_latest_release(&mut self) -> DynResult<()>;
     fn generate_assets_report(&mut self) -> DynResult<()>;
-    fn download_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<&T>;
-    fn fetch_release(&self) -> DynResult<Release>;
+    fn download_release_asset(
+        &self,
+        release_asset: &ReleaseAsset,
+        progress: &ProgressBar,
+    ) -> DynResult<()>;
+    fn verify_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<()>;
+    fn fetch_release(&self) -> DynResult<(Release, AssetsInfo)>;
+    fn get_reframework_releases(&mut self, page: u32, per_page: u32) -> DynResult<Vec<Release>>;
+    fn set_release(&mut self, release: Option<Release>) -> DynResult<()>;
+    fn set_options(&mut self, options: GithubOptions);
+    // human readable origin of releases i.e. praydog/REFramework-nightly
+    fn source_name(&self) -> String;
     fn getRelease(&self) -> Option<&Release>;
     fn getAssetsReport(&self) -> &AssetsReport;
 }
 
 impl ManageGithub for REFRGithub {
     fn get_reframework_latest_release(&mut self) -> DynResult<()> {
-        let release = self.fetch_release()?;
+        let (release, assets_info) = self.fetch_release()?;
         trace!("{:?}", release);
         self.release = Some(release);
+        self.assets_info.extend(assets_info);
         self.generate_assets_report()?;
         trace!("Assets Report: {:#?}", self.report);
         Ok(())
@@ -67,39 +111,20 @@ impl ManageGithub for REFRGithub {
 
     fn generate_assets_report(&mut self) -> DynResult<()> {
         let assets = &self.release.as_ref().ok_or("Release not found")?.assets;
-        assets.iter().try_for_each(|asset| -> DynResult<()> {
-            let game_short_name = GAMES_NEXTGEN_SUPPORT
-                .iter()
-                .find(|short_name| asset.name.contains(*short_name));
-            if let Some(it) = game_short_name {
-                self.report
-                    .entry(it.to_string())
-                    .and_modify(|assets| assets.push(asset.clone()))
-                    .or_insert_with(|| [asset.clone()].to_vec());
-            } else {
-                let short_name = asset.name.split('.').collect::<Vec<&str>>();
-                let short_name = short_name.first().ok_or(format!(
-                    "asset name doesn't follow <%s>.<%s> format i.e. should be RE7.zip found [{}]",
-                    asset.name
-                ))?;
-
-                self.report
-                    .insert(short_name.to_string(), [asset.clone()].to_vec());
-            }
-            Ok(())
-        })?;
+        self.report = generate_assets_report_from(assets)?;
         Ok(())
     }
 
-    // TODO return value should be changed to just DynResult<()> as there is no need to return Self. It makes testing complicated
-    fn download_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<&Self> {
-        let mut download = Download::from_url(&release_asset.download_url);
-        let mut headers = header::HeaderMap::new();
-        headers.insert(header::ACCEPT, "application/octet-stream".parse().unwrap());
-        download.set_headers(headers);
-
-        download.show_progress(true);
-        let folders = get_local_path_to_cache_folder(self.release.as_ref(), None)?;
+    fn download_release_asset(
+        &self,
+        release_asset: &ReleaseAsset,
+        progress: &ProgressBar,
+    ) -> DynResult<()> {
+        let folders = get_local_path_to_cache_folder(
+            self.release.as_ref(),
+            None,
+            self.options.channel.as_deref(),
+        )?;
         fs::create_dir_all(&folders).map_err(|err| {
             format!(
                 "Error during create_dir_all path {} Err {}",
@@ -109,28 +134,165 @@ impl ManageGithub for REFRGithub {
         })?;
 
         let path = &folders.join(&release_asset.name);
-        info!("Downloading {} to {}", release_asset.name, path.display());
-        let mut tmp_archive = fs::File::create(&path).map_err(|err| {
-            format!(
-                "Error during File::create. path {} Err {}",
-                path.display(),
-                err
-            )
-        })?;
+        // asset is written to .part file and renamed only when complete so a broken download never looks like cached one
+        let part_path = get_partial_download_path(path);
+        let downloaded = fs::metadata(&part_path)
+            .map(|metadata| metadata.len())
+            .unwrap_or(0);
+        debug!(
+            "Downloading {} to {}",
+            release_asset.name,
+            part_path.display()
+        );
+        progress.set_message(format!("Downloading {}", release_asset.name));
+
+        let download_url = self.asset_download_url(&release_asset.download_url)?;
+        let mut headers = github_headers(
+            self.token_for_url(&download_url),
+            "application/octet-stream",
+        )?;
+        if downloaded > 0 {
+            debug!("Resuming {} from {} bytes", release_asset.name, downloaded);
+            headers.insert(
+                header::RANGE,
+                format!("bytes={}-", downloaded).parse().unwrap(),
+            );
+        }
+        let resp = reqwest::blocking::Client::builder()
+            .connect_timeout(DOWNLOAD_CONNECT_TIMEOUT)
+            .timeout(DOWNLOAD_TIMEOUT)
+            .build()?
+            .get(&download_url)
+            .headers(headers)
+            .send()?;
+        if resp.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+            // part file already has all bytes, verification will tell if it is fine
+            fs::rename(&part_path, path)?;
+            return Ok(());
+        }
+        check_response_status(&resp, &download_url)?;
+        // server may ignore Range header and send whole file again
+        let resume = resp.status() == StatusCode::PARTIAL_CONTENT;
+        let offset = if resume { downloaded } else { 0 };
+        if let Some(length) = resp.content_length() {
+            progress.set_length(offset + length);
+        }
+        progress.set_position(offset);
 
-        download.download_to(&mut tmp_archive)?;
-        Ok(self)
+        let mut tmp_archive = fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resume)
+            .truncate(!resume)
+            .open(&part_path)
+            .map_err(|err| {
+                format!(
+                    "Error during opening file. path {} Err {}",
+                    part_path.display(),
+                    err
+                )
+            })?;
+
+        io::copy(&mut progress.wrap_read(resp), &mut tmp_archive)?;
+        drop(tmp_archive);
+        fs::rename(&part_path, path)?;
+        Ok(())
     }
 
-    fn fetch_release(&self) -> DynResult<Release> {
-        let api_url = format!(
-            "{}/repos/{}/{}/releases",
-            "https://api.github.com", self.repo_owner, self.repo_name
-        );
-        let release = fetch_release_api(&api_url)?;
+    // checks cached asset against size and digest from GitHub API and if it is a readable zip.
+    // Assets which are not zips (i.e. REFresher executable) are checked by size and digest only
+    fn verify_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<()> {
+        let path = get_local_path_to_cache_folder(
+            self.release.as_ref(),
+            None,
+            self.options.channel.as_deref(),
+        )?
+        .join(&release_asset.name);
+        let asset_info = self.assets_info.get(&release_asset.download_url);
+
+        if let Some(expected_size) = asset_info.and_then(|info| info.size) {
+            let size = fs::metadata(&path)?.len();
+            if size != expected_size {
+                return Err(format!(
+                    "Size of {} is {} but expected {}",
+                    path.display(),
+                    size,
+                    expected_size
+                )
+                .into());
+            }
+        }
+
+        if let Some(digest) = asset_info.and_then(|info| info.digest.as_ref()) {
+            match digest.split_once(':') {
+                Some(("sha256", expected_hash)) => {
+                    let hash = sha256_of_file(&path)?;
+                    if !hash.eq_ignore_ascii_case(expected_hash) {
+                        return Err(format!(
+                            "Digest of {} is sha256:{} but expected {}",
+                            path.display(),
+                            hash,
+                            digest
+                        )
+                        .into());
+                    }
+                }
+                _ => debug!("Unsupported digest {} skipping digest check", digest),
+            }
+        }
+
+        if release_asset.name.to_lowercase().ends_with(".zip") {
+            verify_archive(&path).map_err(|err| format!("{:?}", err))?;
+        }
+        debug!("Asset {} verified", path.display());
+        Ok(())
+    }
+
+    fn fetch_release(&self) -> DynResult<(Release, AssetsInfo)> {
+        let release = fetch_release_api(&self.releases_api_url(), self.options.token.as_deref())?;
         Ok(release)
     }
 
+    fn get_reframework_releases(&mut self, page: u32, per_page: u32) -> DynResult<Vec<Release>> {
+        let releases = fetch_releases_api(
+            &self.releases_api_url(),
+            page,
+            per_page,
+            self.options.token.as_deref(),
+        )?;
+        trace!("Releases page {}: {:?}", page, releases);
+        let releases = releases
+            .into_iter()
+            .map(|(release, assets_info)| {
+                self.assets_info.extend(assets_info);
+                release
+            })
+            .collect();
+        Ok(releases)
+    }
+
+    // replaces the release the manager works on, e.g. with an older one picked from get_reframework_releases
+    fn set_release(&mut self, release: Option<Release>) -> DynResult<()> {
+        self.release = release;
+        if self.release.is_some() {
+            self.generate_assets_report()?;
+        } else {
+            self.report.clear();
+        }
+        Ok(())
+    }
+
+    fn set_options(&mut self, options: GithubOptions) {
+        self.options = options;
+    }
+
+    fn source_name(&self) -> String {
+        match &self.options.api_url {
+            Some(api_url) => format!("{}/{}/{}", api_url, self.repo_owner, self.repo_name),
+            None => format!("{}/{}", self.repo_owner, self.repo_name),
+        }
+    }
+
     fn getRelease(&self) -> Option<&Release> {
         self.release.as_ref()
     }
@@ -140,6 +302,91 @@ impl ManageGithub for REFRGithub {
     }
 }
 
+// groups assets per game short name. Games supporting both types have two assets i.e. RE2.zip and RE2_TDB.zip
+pub fn generate_assets_report_from(assets: &[ReleaseAsset]) -> DynResult<AssetsReport> {
+    let mut report = AssetsReport::new();
+    assets.iter().try_for_each(|asset| -> DynResult<()> {
+        let game_short_name = with_game_registry(|registry| {
+            registry
+                .find_by_asset_name(&asset.name)
+                .map(|entry| entry.short_name.to_string())
+        });
+        let game_short_name = match game_short_name {
+            Some(it) => it,
+            None => asset
+                .name
+                .split('.')
+                .next()
+                .filter(|short_name| !short_name.is_empty())
+                .ok_or(format!(
+                    "asset name doesn't follow <%s>.<%s> format i.e. should be RE7.zip found [{}]",
+                    asset.name
+                ))?
+                .to_string(),
+        };
+        report
+            .entry(game_short_name)
+            .or_default()
+            .push(asset.clone());
+        Ok(())
+    })?;
+    Ok(report)
+}
+
+pub fn sha256_of_file(path: impl AsRef<Path>) -> DynResult<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+impl REFRGithub {
+    fn releases_api_url(&self) -> String {
+        let api_url = self.options.api_url.as_deref().unwrap_or(GITHUB_API_URL);
+        format!(
+            "{}/repos/{}/{}/releases",
+            api_url.trim_end_matches('/'),
+            self.repo_owner,
+            self.repo_name
+        )
+    }
+
+    // token goes only to the GitHub API host, never to an asset_host mirror or proxy
+    fn token_for_url(&self, url: &str) -> Option<&str> {
+        let api_url = self.options.api_url.as_deref().unwrap_or(GITHUB_API_URL);
+        let host = |url: &str| {
+            Url::parse(url)
+                .ok()
+                .and_then(|url| url.host_str().map(|host| host.to_lowercase()))
+        };
+        match host(url) {
+            Some(url_host) if Some(&url_host) == host(api_url).as_ref() => {
+                self.options.token.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    // i.e. https://api.github.com/repos/o/r/releases/assets/1 with asset_host http://mirror:8080/github
+    // becomes http://mirror:8080/github/repos/o/r/releases/assets/1
+    fn asset_download_url(&self, download_url: &str) -> DynResult<String> {
+        let asset_host = match &self.options.asset_host {
+            Some(it) => it,
+            None => return Ok(download_url.to_string()),
+        };
+        let host = Url::parse(asset_host)?;
+        let mut url = Url::parse(download_url)?;
+        let path = format!("{}{}", host.path().trim_end_matches('/'), url.path());
+        url.set_scheme(host.scheme())
+            .map_err(|_| format!("Can't use scheme of asset host {}", asset_host))?;
+        url.set_host(host.host_str())?;
+        url.set_port(host.port())
+            .map_err(|_| format!("Can't use port of asset host {}", asset_host))?;
+        url.set_path(&path);
+        Ok(url.to_string())
+    }
+}
+
 #[cfg_attr(test, automock)]
 impl REFRGithub {
     pub fn new(repo_owner: &str, repo_name: &str) -> Box<dyn ManageGithub> {
@@ -148,6 +395,62 @@ impl REFRGithub {
             repo_name: repo_name.to_owned(),
             release: None,
             report: HashMap::new(),
+            assets_info: HashMap::new(),
+            options: GithubOptions::default(),
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_download_url_uses_asset_host() {
+        let mut github = REFRGithub {
+            repo_owner: "praydog".to_string(),
+            repo_name: "REFramework-nightly".to_string(),
+            ..REFRGithub::default()
+        };
+        let download_url =
+            "https://api.github.com/repos/praydog/REFramework-nightly/releases/assets/1";
+        assert_eq!(
+            github.asset_download_url(download_url).unwrap(),
+            download_url
+        );
+
+        assert_eq!(
+            github.releases_api_url(),
+            "https://api.github.com/repos/praydog/REFramework-nightly/releases"
+        );
+
+        github.set_options(GithubOptions {
+            asset_host: Some("http://127.0.0.1:8080/github/".to_string()),
+            api_url: Some("https://github.example.com/api/v3/".to_string()),
+            ..GithubOptions::default()
+        });
+        assert_eq!(
+            github.asset_download_url(download_url).unwrap(),
+            "http://127.0.0.1:8080/github/repos/praydog/REFramework-nightly/releases/assets/1"
+        );
+        assert_eq!(
+            github.releases_api_url(),
+            "https://github.example.com/api/v3/repos/praydog/REFramework-nightly/releases"
+        );
+    }
+
+    #[test]
+    fn token_is_not_sent_to_asset_host() {
+        let mut github = REFRGithub::default();
+        github.set_options(GithubOptions {
+            token: Some("secret".to_string()),
+            asset_host: Some("http://127.0.0.1:8080/github/".to_string()),
+            ..GithubOptions::default()
+        });
+        let download_url =
+            "https://api.github.com/repos/praydog/REFramework-nightly/releases/assets/1";
+        assert_eq!(github.token_for_url(download_url), Some("secret"));
+        let mirror_url = github.asset_download_url(download_url).unwrap();
+        assert_eq!(github.token_for_url(&mirror_url), None);
+    }
+}
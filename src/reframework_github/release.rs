@@ -1,10 +1,22 @@
+use std::collections::HashMap;
+
 use self_update::update::{Release, ReleaseAsset};
 
 use crate::DynResult;
 
+// expected properties of a downloaded asset, digest is present only when GitHub API provides one
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AssetInfo {
+    pub size: Option<u64>,
+    pub digest: Option<String>,
+}
+
+// AssetInfo per asset download_url
+pub type AssetsInfo = HashMap<String, AssetInfo>;
+
 pub struct ReleaseREFR;
 impl ReleaseREFR {
-    pub fn from_release(release: &serde_json::Value) -> DynResult<Release> {
+    pub fn from_release(release: &serde_json::Value) -> DynResult<(Release, AssetsInfo)> {
         let tag = release["tag_name"]
             .as_str()
             .ok_or("Release missing `tag_name`")?;
@@ -14,24 +26,82 @@ impl ReleaseREFR {
         let name = release["name"].as_str().unwrap_or(tag);
         let assets = release["assets"].as_array().ok_or("No assets found")?;
         let body = release["body"].as_str().map(String::from);
-        let assets = assets
+        let (assets, assets_info): (Vec<ReleaseAsset>, Vec<(String, AssetInfo)>) = assets
             .iter()
             .map(ReleaseREFR::from_asset)
-            .collect::<DynResult<Vec<ReleaseAsset>>>()?;
-        Ok(Release {
-            name: name.to_owned(),
-            version: tag.trim_start_matches('v').to_owned(),
-            date: date.to_owned(),
-            body,
-            assets,
-        })
+            .collect::<DynResult<Vec<(ReleaseAsset, AssetInfo)>>>()?
+            .into_iter()
+            .map(|(asset, info)| {
+                let download_url = asset.download_url.clone();
+                (asset, (download_url, info))
+            })
+            .unzip();
+        Ok((
+            Release {
+                name: name.to_owned(),
+                version: tag.trim_start_matches('v').to_owned(),
+                date: date.to_owned(),
+                body,
+                assets,
+            },
+            assets_info.into_iter().collect(),
+        ))
     }
-    fn from_asset(asset: &serde_json::Value) -> DynResult<ReleaseAsset> {
+    fn from_asset(asset: &serde_json::Value) -> DynResult<(ReleaseAsset, AssetInfo)> {
         let download_url = asset["url"].as_str().ok_or("Asset missing `url`")?;
         let name = asset["name"].as_str().ok_or("Asset missing `name`")?;
-        Ok(ReleaseAsset {
-            download_url: download_url.to_owned(),
-            name: name.to_owned(),
-        })
+        let info = AssetInfo {
+            size: asset["size"].as_u64(),
+            digest: asset["digest"].as_str().map(String::from),
+        };
+        Ok((
+            ReleaseAsset {
+                download_url: download_url.to_owned(),
+                name: name.to_owned(),
+            },
+            info,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_release_records_asset_info() {
+        let release = serde_json::json!({
+            "tag_name": "v1.333-07ab146",
+            "created_at": "2022-08-01T10:00:00Z",
+            "assets": [
+                {
+                    "url": "https://api.github.com/assets/1",
+                    "name": "RE2.zip",
+                    "size": 1024,
+                    "digest": "sha256:abc"
+                },
+                {
+                    "url": "https://api.github.com/assets/2",
+                    "name": "RE8.zip",
+                    "size": 2048
+                }
+            ]
+        });
+        let (release, assets_info) = ReleaseREFR::from_release(&release).unwrap();
+        assert_eq!(release.assets.len(), 2);
+        assert_eq!(
+            assets_info.get("https://api.github.com/assets/1"),
+            Some(&AssetInfo {
+                size: Some(1024),
+                digest: Some("sha256:abc".to_string())
+            })
+        );
+        assert_eq!(
+            assets_info.get("https://api.github.com/assets/2"),
+            Some(&AssetInfo {
+                size: Some(2048),
+                digest: None
+            })
+        );
     }
 }
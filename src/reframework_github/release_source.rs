@@ -0,0 +1,163 @@
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use log::warn;
+
+use crate::{
+    tomlConf::configStruct::{GameConfig, Main},
+    NIGHTLY_RELEASE, REPO_OWNER, STABLE_RELEASE,
+};
+
+pub static LOCAL_SOURCE_PREFIX: &str = "local:";
+
+// one entry of `sources` from main section. Supported entries:
+// "nightly" | "normal" - REFramework-nightly | REFramework repo of repo_owner, "stable" is the same as "normal"
+// "owner/repo"         - any GitHub repo
+// "https://host/api"   - GitHub compatible API base with repo_owner/repo_name repo
+// "local:<folder>"     - folder with <version>/<GAME>.zip files
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReleaseSource {
+    Github {
+        owner: String,
+        repo: String,
+        api_url: Option<String>,
+    },
+    LocalFolder(PathBuf),
+}
+
+impl Display for ReleaseSource {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ReleaseSource::Github {
+                owner,
+                repo,
+                api_url: Some(api_url),
+            } => write!(f, "{}/{}/{}", api_url.trim_end_matches('/'), owner, repo),
+            ReleaseSource::Github { owner, repo, .. } => write!(f, "{}/{}", owner, repo),
+            ReleaseSource::LocalFolder(folder) => {
+                write!(f, "{}{}", LOCAL_SOURCE_PREFIX, folder.display())
+            }
+        }
+    }
+}
+
+impl ReleaseSource {
+    pub fn parse(entry: &str, main: &Main) -> Option<Self> {
+        let entry = entry.trim();
+        let owner = main.repo_owner.as_deref().unwrap_or(REPO_OWNER);
+        let github = |owner: &str, repo: &str, api_url: Option<&str>| ReleaseSource::Github {
+            owner: owner.to_string(),
+            repo: repo.to_string(),
+            api_url: api_url.map(String::from),
+        };
+        if let Some(folder) = entry.strip_prefix(LOCAL_SOURCE_PREFIX) {
+            let folder = folder.trim();
+            return (!folder.is_empty()).then(|| ReleaseSource::LocalFolder(PathBuf::from(folder)));
+        }
+        if entry.starts_with("http://") || entry.starts_with("https://") {
+            let repo = main.repo_name.as_deref().unwrap_or(NIGHTLY_RELEASE);
+            return Some(github(owner, repo, Some(entry)));
+        }
+        match entry {
+            "nightly" => Some(github(owner, NIGHTLY_RELEASE, None)),
+            "normal" | "stable" => Some(github(owner, STABLE_RELEASE, None)),
+            _ => match entry.split_once('/') {
+                Some((owner, repo))
+                    if !owner.is_empty() && !repo.is_empty() && !repo.contains('/') =>
+                {
+                    Some(github(owner, repo, None))
+                }
+                _ => None,
+            },
+        }
+    }
+
+    // sources in order of priority. Without sources repo_owner/repo_name (or their defaults) is the only one
+    pub fn from_config(main: &Main) -> Vec<Self> {
+        let sources: Vec<Self> = main
+            .sources
+            .iter()
+            .flatten()
+            .filter_map(|entry| {
+                let source = ReleaseSource::parse(entry, main);
+                if source.is_none() {
+                    warn!("Release source {} is not supported, skipping it", entry);
+                }
+                source
+            })
+            .collect();
+        if !sources.is_empty() {
+            return sources;
+        }
+        vec![ReleaseSource::Github {
+            owner: main.repo_owner.as_deref().unwrap_or(REPO_OWNER).to_string(),
+            repo: main
+                .repo_name
+                .as_deref()
+                .unwrap_or(NIGHTLY_RELEASE)
+                .to_string(),
+            api_url: None,
+        }]
+    }
+}
+
+// channel set for the game, games without one follow `sources` from main section
+pub fn get_game_channel(game_config: &GameConfig) -> Option<&str> {
+    game_config
+        .channel
+        .as_deref()
+        .map(str::trim)
+        .filter(|channel| !channel.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sources_are_parsed_in_order() {
+        let main = Main {
+            sources: Some(vec![
+                "nightly".to_string(),
+                "local:D:\\mods\\REFramework".to_string(),
+                "ghes.example.com".to_string(),
+                "https://github.example.com/api/v3".to_string(),
+                "someone/REFramework-fork".to_string(),
+                "normal".to_string(),
+            ]),
+            ..Main::default()
+        };
+        let sources = ReleaseSource::from_config(&main);
+        let names: Vec<String> = sources.iter().map(|source| source.to_string()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "praydog/REFramework-nightly",
+                "local:D:\\mods\\REFramework",
+                "https://github.example.com/api/v3/praydog/REFramework-nightly",
+                "someone/REFramework-fork",
+                "praydog/REFramework",
+            ]
+        );
+    }
+
+    #[test]
+    fn repo_from_main_is_used_without_sources() {
+        let main = Main {
+            repo_owner: Some("someone".to_string()),
+            repo_name: Some("REFramework-fork".to_string()),
+            sources: Some(Vec::new()),
+            ..Main::default()
+        };
+        assert_eq!(
+            ReleaseSource::from_config(&main),
+            vec![ReleaseSource::Github {
+                owner: "someone".to_string(),
+                repo: "REFramework-fork".to_string(),
+                api_url: None
+            }]
+        );
+    }
+}
@@ -3,21 +3,32 @@ use mockall::{automock, predicate::*};
 
 use core::fmt;
 use error_stack::{IntoReport, Result, ResultExt};
-use game_scanner::{manager, prelude::Game, steam};
+#[cfg(windows)]
+use game_scanner::steam;
+use game_scanner::{manager, prelude::Game};
 use std::{
     error::Error,
     fmt::{Display, Formatter},
     path::PathBuf,
+    process::Command,
 };
 
-use crate::tomlConf::configStruct::SteamId;
+#[cfg(not(windows))]
+use crate::utils::steam_library::{find_game_locations, find_steam_root};
+use crate::{tomlConf::configStruct::SteamId, utils::launch_args::get_steam_run_url};
 
-pub struct SteamManager;
+// steam_path is steamExePath from config. On Linux games are found in Steam libraries it points to
+// (or in ~/.steam/steam when not set) and launched with its steam launcher
+#[derive(Default)]
+pub struct SteamManager {
+    steam_path: Option<PathBuf>,
+}
 #[derive(Debug, Default)]
 pub enum SteamError {
     #[default]
     other,
     GameNotFoundById(String),
+    SteamNotFound,
 }
 impl Display for SteamError {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> fmt::Result {
@@ -31,23 +42,22 @@ impl Error for SteamError {}
 
 #[cfg_attr(test, automock)]
 pub trait SteamThings {
-    fn get_games_locations(
-        &self,
-        game_ids: &Vec<&'static str>,
-    ) -> SteamResult<Vec<(String, PathBuf)>>;
+    fn get_games_locations(&self, game_ids: &[SteamId]) -> SteamResult<Vec<(String, PathBuf)>>;
     fn run_game(&self, game: &Game) -> SteamResult<()>;
-    fn run_game_via_steam_manager(&self, id: &str) -> SteamResult<()>;
+    fn run_game_via_steam_manager(&self, id: &str, args: &[String]) -> SteamResult<()>;
+    fn set_steam_path(&mut self, steam_path: Option<PathBuf>);
 }
 
+#[cfg(windows)]
 impl SteamThings for SteamManager {
-    fn get_games_locations(&self, game_ids: &Vec<&str>) -> SteamResult<Vec<(String, PathBuf)>> {
+    fn get_games_locations(&self, game_ids: &[SteamId]) -> SteamResult<Vec<(String, PathBuf)>> {
         let games = steam::games()
             .report()
             .change_context(SteamError::default())?;
 
         let game_path_vec: Vec<(SteamId, PathBuf)> = games
             .iter()
-            .filter_map(|game| match game_ids.iter().any(|id| *id == game.id) {
+            .filter_map(|game| match game_ids.contains(&game.id) {
                 true => Some((game.id.clone(), game.path.to_owned()?)),
                 false => None,
             })
@@ -62,7 +72,25 @@ impl SteamThings for SteamManager {
         Ok(())
     }
 
-    fn run_game_via_steam_manager(&self, id: &str) -> SteamResult<()> {
+    // game_scanner can't pass arguments, Steam gets them through steam://run url then
+    fn run_game_via_steam_manager(&self, id: &str, args: &[String]) -> SteamResult<()> {
+        if !args.is_empty() {
+            let url = get_steam_run_url(id, args);
+            let mut command = match self.steam_path.as_ref().filter(|path| path.is_file()) {
+                Some(steam_exe) => Command::new(steam_exe),
+                None => {
+                    let mut command = Command::new("cmd");
+                    command.args(["/C", "start", ""]);
+                    command
+                }
+            };
+            command
+                .arg(url)
+                .spawn()
+                .report()
+                .change_context(SteamError::GameNotFoundById(id.to_string()))?;
+            return Ok(());
+        }
         let game = steam::find(id)
             .report()
             .change_context(SteamError::GameNotFoundById(id.to_string()))?;
@@ -71,12 +99,46 @@ impl SteamThings for SteamManager {
             .change_context(SteamError::default())?;
         Ok(())
     }
+
+    // game_scanner finds Steam on its own
+    fn set_steam_path(&mut self, steam_path: Option<PathBuf>) {
+        self.steam_path = steam_path;
+    }
 }
 
-/* TODO
-     game.commands.launch = Some(vec![
-        launcher_executable.display().to_string(),
-        String::from("-silent"),
-        format!("steam://run/{}", &game.id),
-    ]);
-*/
+#[cfg(not(windows))]
+impl SteamThings for SteamManager {
+    fn get_games_locations(&self, game_ids: &[SteamId]) -> SteamResult<Vec<(String, PathBuf)>> {
+        let steam_root = find_steam_root(self.steam_path.as_deref())
+            .ok_or(SteamError::SteamNotFound)
+            .report()?;
+        Ok(find_game_locations(&steam_root, game_ids))
+    }
+
+    fn run_game(&self, game: &Game) -> SteamResult<()> {
+        manager::launch_game(game)
+            .report()
+            .change_context(SteamError::default())?;
+        Ok(())
+    }
+
+    // Proton games are started by Steam the same way as native ones
+    fn run_game_via_steam_manager(&self, id: &str, args: &[String]) -> SteamResult<()> {
+        let launcher = self
+            .steam_path
+            .as_ref()
+            .filter(|path| path.is_file())
+            .map(|path| path.as_os_str().to_owned())
+            .unwrap_or_else(|| "steam".into());
+        Command::new(launcher)
+            .arg(get_steam_run_url(id, args))
+            .spawn()
+            .report()
+            .change_context(SteamError::GameNotFoundById(id.to_string()))?;
+        Ok(())
+    }
+
+    fn set_steam_path(&mut self, steam_path: Option<PathBuf>) {
+        self.steam_path = steam_path;
+    }
+}
@@ -1,9 +1,18 @@
 use crate::{
-    dialogs::dialogs_label::LabelOptions, rManager::rManager_header::REvilManager,
-    rManager::rManager_header::REvilThings, ARGS,
+    args::{get_command, CacheAction, Command, ConfigAction},
+    dialogs::dialogs_label::LabelOptions,
+    rManager::cleanup_cache::remove_partial_downloads,
+    rManager::rManager_header::REvilManager,
+    rManager::rManager_header::REvilThings,
+    utils::get_local_path_to_cache::CACHE_FOLDER,
+    ARGS, PARTIAL_DOWNLOAD_MAX_AGE_DAYS,
 };
 use error_stack::ResultExt;
 use log::{error, info, warn, Level};
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
 
 pub struct StrategyFactory;
 
@@ -13,11 +22,15 @@ trait Strategy {
 
 impl StrategyFactory {
     pub fn get_strategy(manager: &mut REvilManager) -> Box<fn(&mut REvilManager)> {
-        let run = get_args();
+        let (run, release) = get_args();
 
         manager.state.selected_option = Some(LabelOptions::GoTop);
-        if run == "none" {
+        if get_command().is_some() {
+            Box::new(RunCommand::run)
+        } else if run == "none" {
             Box::new(DefaultRoute::run)
+        } else if release.is_some() {
+            Box::new(InstallReleaseAndRunTheGame::run)
         } else {
             Box::new(CheckUpdateAndRunTheGame::run)
         }
@@ -28,7 +41,8 @@ struct DefaultRoute;
 impl Strategy for DefaultRoute {
     fn run(manager: &mut REvilManager) {
         EarlyLoad::run(manager);
-        manager.or_log_err(|this| this.generate_ms_links(), Level::Warn);
+        manager.or_log_err(|this| this.check_for_self_update(), Level::Warn);
+        manager.or_log_err(|this| this.generate_shortcuts(), Level::Warn);
         manager
             .check_for_REFramework_update()
             .and_then(|this| this.decision_loop())
@@ -41,6 +55,7 @@ struct CheckUpdateAndRunTheGame;
 impl Strategy for CheckUpdateAndRunTheGame {
     fn run(manager: &mut REvilManager) {
         EarlyLoad::run(manager);
+        manager.or_log_err(|this| this.check_for_self_update(), Level::Warn);
         manager
             .check_for_REFramework_update()
             .and_then(|this| this.pick_one_game_from_report_and_set_as_selected())
@@ -54,6 +69,115 @@ impl Strategy for CheckUpdateAndRunTheGame {
     }
 }
 
+struct InstallReleaseAndRunTheGame;
+impl Strategy for InstallReleaseAndRunTheGame {
+    fn run(manager: &mut REvilManager) {
+        EarlyLoad::run(manager);
+        manager
+            .check_for_REFramework_update()
+            .and_then(|this| this.install_release_from_args())
+            .and_then(|this| this.save_config())
+            .unwrap();
+        LaunchAndSave::run(manager);
+    }
+}
+
+// non-interactive route. Exits with non-zero code on error
+struct RunCommand;
+impl Strategy for RunCommand {
+    fn run(manager: &mut REvilManager) {
+        let command = match get_command() {
+            Some(it) => it,
+            None => return,
+        };
+        if command == Command::Rollback {
+            if let Err(err) = manager.rollback_self_update() {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        // config commands work on the file as it is, so neither Steam detection nor saving happens
+        if let Command::Config { action } = &command {
+            let result = match action {
+                ConfigAction::Check => manager.check_config(),
+                ConfigAction::Restore { number } => manager.restore_config(*number),
+                ConfigAction::Get { key } => manager.get_config_value(key),
+                ConfigAction::Set { key, value } => manager.set_config_value(key, value),
+            };
+            if let Err(err) = result {
+                eprintln!("{}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        // cache commands work on cache recorded in config, so partial downloads, Steam and local files are left as they are
+        if let Command::Cache {
+            action: CacheAction::Prune { dry_run },
+        } = command
+        {
+            let result = manager
+                .load_config()
+                .and_then(|this| this.prune_cache(dry_run))
+                .and_then(|this| match dry_run {
+                    true => Ok(this),
+                    false => this.save_config(),
+                });
+            if let Err(err) = result {
+                error!("{:?}", err);
+                std::process::exit(1);
+            }
+            return;
+        }
+        EarlyLoad::run(manager);
+        let result = match command {
+            Command::Status { format } => {
+                manager.or_log_err(|this| this.check_for_REFramework_update(), Level::Warn);
+                manager.print_status(&format)
+            }
+            Command::Update { game, all, prefer } => manager
+                .check_for_REFramework_update()
+                .and_then(|this| {
+                    let games = if all {
+                        this.state.games_that_require_update.clone()
+                    } else {
+                        game.into_iter().collect()
+                    };
+                    this.select_games_to_update(
+                        &games,
+                        prefer.as_ref().map(|prefer| prefer.is_nextgen()),
+                    )
+                })
+                .and_then(|this| this.download_REFramework_update())
+                .and_then(|this| this.unzip_updates().after_unzip_work(None))
+                .and_then(|this| this.save_config()),
+            Command::SwitchRuntime { game, runtime } => {
+                manager.switch_runtime_for_game(&game, runtime.to_runtime())
+            }
+            Command::SwitchType { game } => manager.switch_type_for_game(&game),
+            Command::LoadCache { game, version } => {
+                manager.load_from_cache_for_game(&game, &version)
+            }
+            Command::Rescan => {
+                manager.state.selected_option = Some(LabelOptions::RescanLocal);
+                manager.rescan_option().and_then(|this| this.save_config())
+            }
+            Command::Launch { game } => manager
+                .select_game_to_launch(&game)
+                .and_then(|this| this.launch_game())
+                .and_then(|this| this.save_config()),
+            Command::Register { game, path } => manager
+                .register_game(&game, &path)
+                .and_then(|this| this.save_config()),
+            Command::Config { .. } | Command::Cache { .. } | Command::Rollback => Ok(manager),
+        };
+        if let Err(err) = result {
+            error!("{:?}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
 struct LaunchAndSave;
 impl Strategy for LaunchAndSave {
     fn run(manager: &mut REvilManager) {
@@ -75,16 +199,24 @@ impl Strategy for EarlyLoad {
             },
             Level::Warn,
         );
-        match manager.load_games_from_steam() {
+        // game can be registered manually even when there is neither config nor Steam
+        let registering = matches!(get_command(), Some(Command::Register { .. }));
+        match manager.load_games_from_steam().map(|_| ()) {
             Ok(_) => info!("Auto-detect steam games done!"),
-            Err(err) => manager
-                .state
-                .config_loading_error_ocurred
-                .then(|| {
-                     error!("Error loading config file and error steam detection: {err}. Make sure steam is installed correctly");
-                     panic!();
-                })
-                .unwrap_or(warn!("{err}")),
+            Err(err) if manager.state.config_loading_error_ocurred && !registering => {
+                error!("Error loading config file and error steam detection: {err}. Make sure steam is installed correctly or register game folder with `register <GAME> <PATH>` command");
+                register_game_or_exit(manager);
+            }
+            Err(err) => warn!("{err}"),
+        };
+        match remove_partial_downloads(
+            Path::new(CACHE_FOLDER),
+            Duration::from_secs(PARTIAL_DOWNLOAD_MAX_AGE_DAYS * 24 * 60 * 60),
+            SystemTime::now(),
+        ) {
+            Ok(0) => (),
+            Ok(removed) => info!("Removed {} stale partial downloads from cache", removed),
+            Err(err) => warn!("Couldn't remove partial downloads from cache: {}", err),
         };
         // only check local files again when a config failed to be loaded or a steam found the new game
         if manager.state.config_loading_error_ocurred || manager.state.new_steam_game_found {
@@ -93,12 +225,36 @@ impl Strategy for EarlyLoad {
     }
 }
 
-fn get_args() -> String {
+// without config and Steam only a manually registered game can be used, so dialog asks for one
+fn register_game_or_exit(manager: &mut REvilManager) {
+    if get_command().is_none() {
+        let selected_option = manager
+            .state
+            .selected_option
+            .replace(LabelOptions::RegisterGameSection);
+        manager.or_log_err(|this| this.register_game_if_chosen(), Level::Error);
+        manager.state.selected_option = selected_option;
+    }
+    if manager
+        .config
+        .games
+        .values()
+        .any(|game_config| game_config.location.is_some())
+    {
+        return;
+    }
+    eprintln!("No game found. Install Steam or register game folder with `register <GAME> <PATH>` command");
+    std::process::exit(1);
+}
+
+fn get_args() -> (String, Option<String>) {
     let mut run = "none".to_string();
+    let mut release = None;
     unsafe {
         if let Some(args) = &ARGS {
             run = args.one.clone();
+            release = args.release.clone();
         };
     }
-    run
+    (run, release)
 }
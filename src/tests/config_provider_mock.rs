@@ -52,8 +52,7 @@ pub mod mock_conf_provider {
         runtime = "OpenVR"
         latawiec = true
     "#;
-            let (main, games) = deserialize(content).unwrap();
-            Ok(REvilConfig { main, games })
+            Ok(deserialize(content).unwrap())
         };
         Box::new(default)
     }
@@ -0,0 +1,68 @@
+#[cfg(test)]
+pub mod local_server {
+    use std::{
+        collections::HashMap,
+        io::{BufRead, BufReader, Cursor, Write},
+        net::TcpListener,
+        thread,
+    };
+
+    use zip::write::FileOptions;
+
+    // minimal local HTTP server answering GET requests by path (without query) with canned bodies.
+    // Returns base url i.e. http://127.0.0.1:50123
+    pub fn start_http_stand_in(routes: HashMap<String, (String, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let mut stream = match stream {
+                    Ok(it) => it,
+                    Err(_) => continue,
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap_or_default();
+                // headers are not needed so only read them to the end
+                let mut line = String::new();
+                while reader.read_line(&mut line).unwrap_or_default() > 2 {
+                    line.clear();
+                }
+                let path = request_line
+                    .split_whitespace()
+                    .nth(1)
+                    .unwrap_or_default()
+                    .split('?')
+                    .next()
+                    .unwrap_or_default();
+                let response = match routes.get(path) {
+                    Some((content_type, body)) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            content_type,
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend(body);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                stream.write_all(&response).unwrap_or_default();
+            }
+        });
+        base_url
+    }
+
+    pub fn zip_with_files(files: &[&str]) -> Vec<u8> {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        files.iter().for_each(|file| {
+            zip.start_file(*file, FileOptions::default()).unwrap();
+            zip.write_all(b"stand-in").unwrap();
+        });
+        zip.finish().unwrap().into_inner()
+    }
+}
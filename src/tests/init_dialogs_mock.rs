@@ -28,7 +28,7 @@ pub(crate) fn init_dialogs_mocks() -> (
     Box<Dialogs>,
 ) {
     INIT.call_once(|| {
-        // init_logger("debug"); // uncomment if need more data for debugging
+        // init_logger("debug", false); // uncomment if need more data for debugging
     });
 
     let (_ctx, refr_constr) = prepare_refr_github_mock_and_get_constr();
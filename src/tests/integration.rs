@@ -16,6 +16,12 @@ pub mod tests {
     use crate::tomlConf::configStruct::{ConfigError, ErrorLevel, Runtime};
     use crate::utils::local_version::LocalGameConfig;
     use crate::ARGS;
+    use crate::{
+        rManager::rManager_header::REvilThings,
+        refr_github::REFRGithub,
+        tests::http_stand_in::local_server::{start_http_stand_in, zip_with_files},
+    };
+    use std::{collections::HashMap, fs, path::Path};
 
     #[test]
     fn default_route() {
@@ -26,6 +32,7 @@ pub mod tests {
                     level: ErrorLevel::info,
                     one: "none".to_string(),
                     run: RunAfter::no,
+                    ..ArgsClap::default()
                 });
             }
             let (
@@ -48,12 +55,12 @@ pub mod tests {
 
             steam_menago
                 .expect_run_game_via_steam_manager()
-                .withf(|x| {
+                .withf(|x, _| {
                     assert_eq!(x.to_string(), id.to_string()); // added assert_eq! to have better error message without it is not obvious what really happened
                     true
                 })
                 .once()
-                .returning(|_| Ok(()));
+                .returning(|_, _| Ok(()));
 
             config_provider_mock
                 .expect_load_from_file()
@@ -111,6 +118,7 @@ pub mod tests {
                     level: ErrorLevel::info,
                     one: "none".to_string(),
                     run: RunAfter::no,
+                    ..ArgsClap::default()
                 });
             }
             let (
@@ -126,17 +134,17 @@ pub mod tests {
             if *steam_id != "952060" {
                 steam_menago
                     .expect_run_game_via_steam_manager()
-                    .withf(|x| {
+                    .withf(|x, _| {
                         assert_eq!(x.to_string(), id.to_string());
                         true
                     })
                     .once()
-                    .returning(|_| Ok(()));
+                    .returning(|_, _| Ok(()));
             } else {
                 steam_menago
                     .expect_run_game_via_steam_manager()
                     .never()
-                    .returning(|_| Ok(()));
+                    .returning(|_, _| Ok(()));
             }
 
             config_provider_mock
@@ -224,7 +232,7 @@ pub mod tests {
             steam_menago
                 .expect_run_game_via_steam_manager()
                 .once()
-                .returning(|_| Ok(()));
+                .returning(|_, _| Ok(()));
 
             let mut evil_manager = REvilManager::new(
                 config_provider_mock,
@@ -239,6 +247,7 @@ pub mod tests {
                     level: ErrorLevel::info,
                     one: short_name.to_string(),
                     run: RunAfter::yes,
+                    ..ArgsClap::default()
                 });
             }
             let strategy = StrategyFactory::get_strategy(&mut evil_manager);
@@ -286,7 +295,7 @@ pub mod tests {
             steam_menago
                 .expect_run_game_via_steam_manager()
                 .once()
-                .returning(|_| Ok(()));
+                .returning(|_, _| Ok(()));
             let mock_ask = MockAsk::new();
 
             let mut dialogs = Box::new(mock_ask);
@@ -309,6 +318,7 @@ pub mod tests {
                     level: ErrorLevel::info,
                     one: short_name.to_string(),
                     run: RunAfter::yes,
+                    ..ArgsClap::default()
                 });
             }
             let strategy = StrategyFactory::get_strategy(&mut evil_manager);
@@ -323,15 +333,8 @@ pub mod tests {
                     &"v1.333-07ab146".to_string()
                 );
                 assert_eq!(
-                    re3_conf
-                        .versions
-                        .as_ref()
-                        .unwrap()
-                        .first()
-                        .unwrap()
-                        .first()
-                        .unwrap(),
-                    &"v1.333-07ab146".to_string()
+                    re3_conf.versions.as_ref().unwrap().first().unwrap().version,
+                    "v1.333-07ab146"
                 );
             }
             if short_name == &"RE2" {
@@ -343,18 +346,76 @@ pub mod tests {
                     &"07ab146".to_string()
                 );
                 assert_eq!(
-                    re2_conf
-                        .versions
-                        .as_ref()
-                        .unwrap()
-                        .first()
-                        .unwrap()
-                        .first()
-                        .unwrap(),
-                    &"07ab146".to_string()
+                    re2_conf.versions.as_ref().unwrap().first().unwrap().version,
+                    "07ab146"
                 );
             }
         })
     }
+
+    #[test]
+    fn real_github_manager_against_http_stand_in() {
+        unsafe {
+            ARGS = Some(ArgsClap {
+                level: ErrorLevel::info,
+                one: "none".to_string(),
+                run: RunAfter::no,
+                ..ArgsClap::default()
+            });
+        }
+        let asset = zip_with_files(&["dinput8.dll", "reframework/plugins/plugin.dll"]);
+        let release = serde_json::json!([{
+            "tag_name": "v9.999-stand-in",
+            "created_at": "2022-08-01T10:00:00Z",
+            "assets": [{
+                "url": "https://api.github.com/repos/praydog/REFramework-nightly/releases/assets/8",
+                "name": "RE8.zip",
+                "size": asset.len()
+            }]
+        }]);
+        let mut routes = HashMap::new();
+        routes.insert(
+            "/repos/praydog/REFramework-nightly/releases".to_string(),
+            (
+                "application/json".to_string(),
+                release.to_string().into_bytes(),
+            ),
+        );
+        routes.insert(
+            "/repos/praydog/REFramework-nightly/releases/assets/8".to_string(),
+            ("application/octet-stream".to_string(), asset),
+        );
+        let base_url = start_http_stand_in(routes);
+
+        let (steam_menago, local_provider_mock, dialogs, mut config_provider_mock, _ctx, _) =
+            init_manager_mocks();
+        config_provider_mock
+            .expect_load_from_file()
+            .returning(load_from_file_default_return_mock());
+        let mut evil_manager = REvilManager::new(
+            config_provider_mock,
+            local_provider_mock,
+            steam_menago,
+            dialogs,
+            REFRGithub::new,
+        );
+        evil_manager.load_config().unwrap();
+        evil_manager.config.main.github_api_url = Some(base_url.clone());
+        evil_manager.config.main.asset_host = Some(base_url);
+
+        evil_manager
+            .check_for_REFramework_update()
+            .and_then(|this| this.select_games_to_update(&["RE8".to_string()], None))
+            .and_then(|this| this.download_REFramework_update())
+            .and_then(|this| this.unzip_updates().after_unzip_work(None))
+            .unwrap();
+
+        let cache_folder = Path::new("refr_cache/v9.999-stand-in");
+        let cached = cache_folder.join("RE8.zip").exists();
+        fs::remove_dir_all(cache_folder).unwrap();
+        assert!(cached);
+        let game = evil_manager.config.games.get("RE8").unwrap();
+        assert_eq!(game.version_in_use, Some("v9.999-stand-in".to_string()));
+    }
 }
 // TODO maybe test where there is a new steam discovery i.e. move out game_path_vec from steam_mock to pub fn and then for new one concatenate with desired vec
@@ -4,7 +4,7 @@ use crate::utils::local_version::MockLocalFiles;
 
 pub(crate) fn mock_local_provider_w_defaults(local_provider_mock: &mut Box<MockLocalFiles>) {
     local_provider_mock
-        .expect_create_ms_lnk()
+        .expect_create_shortcut()
         .returning(|_, _, _| Ok(()));
     local_provider_mock
         .expect_create_cache_dir()
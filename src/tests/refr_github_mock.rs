@@ -1,12 +1,15 @@
 use std::collections::HashMap;
 
+use indicatif::ProgressBar;
 use lazy_static::lazy_static;
 use self_update::update::ReleaseAsset;
 
 use self_update::update::Release;
 
 use crate::reframework_github::refr_github::AssetsReport;
+use crate::reframework_github::refr_github::GithubOptions;
 use crate::reframework_github::refr_github::MockREFRGithub;
+use crate::reframework_github::release::AssetsInfo;
 use crate::DynResult;
 
 use mockall::mock;
@@ -19,8 +22,13 @@ mock!(
     impl ManageGithub<REFRGithub> for ManageGithubM<REFRGithub> {
         pub fn get_reframework_latest_release(&mut self) -> DynResult<()>;
         pub fn generate_assets_report(&mut self) -> DynResult<()>;
-        pub fn download_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<&'static REFRGithub>;
-        pub fn fetch_release(&self) -> DynResult<Release>;
+        pub fn download_release_asset(&self, release_asset: &ReleaseAsset, progress: &ProgressBar) -> DynResult<()>;
+        pub fn verify_release_asset(&self, release_asset: &ReleaseAsset) -> DynResult<()>;
+        pub fn fetch_release(&self) -> DynResult<(Release, AssetsInfo)>;
+        pub fn get_reframework_releases(&mut self, page: u32, per_page: u32) -> DynResult<Vec<Release>>;
+        pub fn set_release(&mut self, release: Option<Release>) -> DynResult<()>;
+        pub fn set_options(&mut self, options: GithubOptions);
+        pub fn source_name(&self) -> String;
         pub fn getRelease(&self) -> Option<&'static Release>;
         pub fn getAssetsReport(&self) -> &'static AssetsReport;
     }
@@ -93,7 +101,6 @@ lazy_static! {
 
         m
     };
-    static ref REFRGithub_STATIC: REFRGithub = REFRGithub::default();
 }
 
 pub fn prepare_refr_github_mock_and_get_constr() -> (
@@ -111,7 +118,13 @@ pub fn prepare_refr_github_mock_and_get_constr() -> (
             mock.expect_getRelease().return_const(Some(&RELEASE));
             mock.expect_getAssetsReport().return_const(&*ASSETS_REPORT);
             mock.expect_download_release_asset()
-                .returning(|_| Ok(&REFRGithub_STATIC));
+                .returning(|_, _| Ok(()));
+            mock.expect_verify_release_asset().returning(|_| Ok(()));
+            mock.expect_set_options().return_const(());
+            mock.expect_get_reframework_releases()
+                .returning(|_, _| Ok(Vec::new()));
+            mock.expect_source_name()
+                .return_const("praydog/REFramework-nightly".to_string());
         }
         Box::new(mock)
     });
@@ -3,6 +3,7 @@ use std::path::Path;
 use crate::steam::MockSteamThings;
 
 pub fn prepare_steam_mock(steam_menago: &mut Box<MockSteamThings>) {
+    steam_menago.expect_set_steam_path().return_const(());
     steam_menago.expect_get_games_locations().returning(|_| {
         let game_path_vec = [
             (
@@ -13,10 +13,18 @@ use super::configStruct::{
     ConfigError::{self, *},
     REvilConfig,
 };
-use super::utils::{deserialize, serialize};
+use super::document::{get_config_value, set_config_value, update_document};
+use super::utils::{check, deserialize, serialize};
+use crate::MAX_CONFIG_BACKUPS;
 
 pub type ConfigResult<T> = Result<T, ConfigError>;
 
+// config which failed to load is copied here before it gets overwritten by the new one
+pub static BROKEN_CONFIG_EXTENSION: &str = "broken";
+// previous good configs are kept as <config>.bak.<N>, 1 is the newest
+pub static CONFIG_BACKUP_EXTENSION: &str = "bak";
+static TEMP_CONFIG_EXTENSION: &str = "tmp";
+
 pub struct REvilConfigProvider {
     filename: String,
 }
@@ -25,35 +33,153 @@ pub struct REvilConfigProvider {
 pub trait ConfigProvider {
     fn load_from_file(&self) -> ConfigResult<REvilConfig>;
     fn save_to_file(&self, config: &REvilConfig) -> ConfigResult<()>;
+    fn check_file(&self) -> ConfigResult<Vec<String>>;
+    fn restore_backup(&self, number: u8) -> ConfigResult<String>;
+    fn get_value(&self, key: &str) -> ConfigResult<String>;
+    fn set_value(&self, key: &str, value: &str) -> ConfigResult<()>;
 }
 
 impl ConfigProvider for REvilConfigProvider {
     fn load_from_file(&self) -> ConfigResult<REvilConfig> {
-        let content = fs::read_to_string(&self.filename)
-            .report()
-            .change_context(ConfigFile)
-            .attach_printable_lazy(|| format!("Error reading {}", &self.filename))?;
+        let content = self.read_content()?;
 
-        let (main, games) = deserialize(&content).change_context(Deserializer)?;
-        Ok(REvilConfig { main, games })
+        deserialize(&content).map_err(|err| {
+            let backup_path = get_broken_config_path(&self.filename);
+            match fs::copy(&self.filename, &backup_path) {
+                Ok(_) => err
+                    .attach_printable(format!("Config file has been backed up to {}", backup_path)),
+                Err(copy_err) => err.attach_printable(format!(
+                    "Backup of config file to {} failed {}",
+                    backup_path, copy_err
+                )),
+            }
+        })
     }
 
     fn save_to_file(&self, config: &REvilConfig) -> ConfigResult<()> {
         let content = serialize(config)?;
-        let mut file = File::create(&self.filename)
-            .report()
-            .change_context(ConfigFile)?;
-        file.write(content.as_bytes())
+        // when file is there only changed keys are written to it
+        let content = match fs::read_to_string(&self.filename) {
+            Ok(current_content) => update_document(&current_content, &content),
+            Err(_) => content,
+        };
+        self.write_atomically(
+            &content,
+            config.main.config_backups.unwrap_or(MAX_CONFIG_BACKUPS),
+        )
+    }
+
+    fn check_file(&self) -> ConfigResult<Vec<String>> {
+        let content = self.read_content()?;
+        Ok(check(&content))
+    }
+
+    // returns path of the restored backup
+    fn restore_backup(&self, number: u8) -> ConfigResult<String> {
+        let backup_path = get_config_backup_path(&self.filename, number);
+        let content = fs::read_to_string(&backup_path)
             .report()
-            .change_context(ConfigFile)?;
-        Ok(())
+            .change_context(ConfigFile)
+            .attach_printable_lazy(|| format!("Error reading backup {}", backup_path))?;
+        let config = deserialize(&content)
+            .attach_printable_lazy(|| format!("Backup {} is not a valid config", backup_path))?;
+        self.write_atomically(
+            &content,
+            config.main.config_backups.unwrap_or(MAX_CONFIG_BACKUPS),
+        )?;
+        Ok(backup_path)
+    }
+
+    fn get_value(&self, key: &str) -> ConfigResult<String> {
+        let content = self.read_content()?;
+        get_config_value(&content, key)
+    }
+
+    fn set_value(&self, key: &str, value: &str) -> ConfigResult<()> {
+        let content = set_config_value(&self.read_content()?, key, value)?;
+        let backups_to_keep = deserialize(&content)
+            .ok()
+            .and_then(|config| config.main.config_backups)
+            .unwrap_or(MAX_CONFIG_BACKUPS);
+        self.write_atomically(&content, backups_to_keep)
     }
 }
 
+pub fn get_broken_config_path(filename: &str) -> String {
+    format!("{}.{}", filename, BROKEN_CONFIG_EXTENSION)
+}
+
 impl REvilConfigProvider {
     pub fn new(path: impl AsRef<Path>) -> Self {
         REvilConfigProvider {
             filename: path.as_ref().to_str().unwrap().to_owned(),
         }
     }
+
+    fn read_content(&self) -> ConfigResult<String> {
+        fs::read_to_string(&self.filename)
+            .report()
+            .change_context(ConfigFile)
+            .attach_printable_lazy(|| format!("Error reading {}", &self.filename))
+    }
+
+    // content goes to a temp file first which replaces config only when fully written,
+    // so a crash in the middle never leaves config truncated
+    fn write_atomically(&self, content: &str, backups_to_keep: u8) -> ConfigResult<()> {
+        let temp_path = format!("{}.{}", self.filename, TEMP_CONFIG_EXTENSION);
+        let mut file = File::create(&temp_path)
+            .report()
+            .change_context(ConfigFile)
+            .attach_printable_lazy(|| format!("Error creating {}", temp_path))?;
+        file.write_all(content.as_bytes())
+            .and_then(|_| file.sync_all())
+            .report()
+            .change_context(ConfigFile)
+            .attach_printable_lazy(|| format!("Error writing {}", temp_path))?;
+        drop(file);
+        self.rotate_backups(content, backups_to_keep)?;
+        fs::rename(&temp_path, &self.filename)
+            .report()
+            .change_context(ConfigFile)
+            .attach_printable_lazy(|| format!("Error replacing {}", self.filename))
+    }
+
+    // current config becomes backup 1 unless it is broken or the same as the new one
+    fn rotate_backups(&self, new_content: &str, backups_to_keep: u8) -> ConfigResult<()> {
+        let current_content = match fs::read_to_string(&self.filename) {
+            Ok(it) => it,
+            Err(_) => return Ok(()),
+        };
+        if backups_to_keep == 0
+            || current_content == new_content
+            || deserialize(&current_content).is_err()
+        {
+            return Ok(());
+        }
+        let rotate = || -> std::io::Result<()> {
+            let oldest = get_config_backup_path(&self.filename, backups_to_keep);
+            if Path::new(&oldest).exists() {
+                fs::remove_file(&oldest)?;
+            }
+            for number in (1..backups_to_keep).rev() {
+                let backup_path = get_config_backup_path(&self.filename, number);
+                if Path::new(&backup_path).exists() {
+                    fs::rename(
+                        &backup_path,
+                        get_config_backup_path(&self.filename, number + 1),
+                    )?;
+                }
+            }
+            fs::copy(&self.filename, get_config_backup_path(&self.filename, 1))?;
+            Ok(())
+        };
+        rotate()
+            .report()
+            .change_context(ConfigFile)
+            .attach_printable("Error rotating config backups")
+    }
+}
+
+pub fn get_config_backup_path(filename: &str, number: u8) -> String {
+    format!("{}.{}.{}", filename, CONFIG_BACKUP_EXTENSION, number)
 }
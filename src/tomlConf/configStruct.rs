@@ -1,28 +1,41 @@
 use serde::{Deserialize, Serialize};
 use std::{collections::HashMap, error::Error, fmt::Debug};
+use toml::Value;
+
+use crate::{utils::game_registry::game_supports_both_editions, STANDARD_TYPE_QUALIFIER};
+
+// 1 - versions as [version, asset names...] arrays with sources in version_sources, 2 - version records
+pub const CONFIG_SCHEMA_VERSION: u32 = 2;
 
 #[derive(Debug)]
 pub enum ConfigError {
     ConfigFile,
     Deserializer,
     Serializer,
+    UnknownKey(String),
+    InvalidValue(String),
 }
 
 impl std::fmt::Display for ConfigError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("ConfigError")
+        match self {
+            ConfigError::UnknownKey(key) => write!(f, "Unknown config key {}", key),
+            ConfigError::InvalidValue(info) => write!(f, "Invalid config value {}", info),
+            _ => f.write_str("ConfigError"),
+        }
     }
 }
 
 impl Error for ConfigError {}
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, clap::ValueEnum, Clone)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, clap::ValueEnum, Clone, Default)]
 pub enum ErrorLevel {
     info,
     debug,
     warn,
     error,
     trace,
+    #[default]
     none,
 }
 
@@ -59,35 +72,169 @@ impl Runtime {
     }
 }
 
+// REFramework build type, games supporting both have Nextgen (i.e. RE2.zip) and Standard (i.e. RE2_TDB.zip) assets
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Edition {
+    nextgen,
+    standard,
+}
+
+impl Edition {
+    // None for games which have only one edition
+    pub fn from_asset_name(game_short_name: &str, asset_name: &str) -> Option<Edition> {
+        if !game_supports_both_editions(game_short_name) {
+            return None;
+        }
+        match asset_name.contains(STANDARD_TYPE_QUALIFIER) {
+            true => Some(Edition::standard),
+            false => Some(Edition::nextgen),
+        }
+    }
+}
+
+// why the latest version record is going to be replaced by the next download
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PendingAction {
+    // mod type is being switched, assets of the other type are kept
+    switch,
+    // cached archive of the version in use is missing
+    update,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct VersionAsset {
+    pub name: String,
+    pub edition: Option<Edition>,
+}
+
+// mod version of a game with its cached assets. assets has to be the last field as toml requires tables after plain values
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default, Clone)]
+pub struct VersionRecord {
+    // release name i.e. v1.460-a1b2c3d or only a hash when read from installed mod
+    pub version: String,
+    pub tag: Option<String>,
+    // release creation date and time of the download
+    pub released: Option<String>,
+    pub downloaded: Option<String>,
+    // release source which supplied the version i.e. praydog/REFramework-nightly
+    pub source: Option<String>,
+    pub pending: Option<PendingAction>,
+    // when true the version is never removed from cache
+    pub keep: Option<bool>,
+    // hand written records without assets are still valid
+    #[serde(default)]
+    pub assets: Vec<VersionAsset>,
+}
+
+impl VersionRecord {
+    pub fn new(version: &str) -> Self {
+        VersionRecord {
+            version: version.to_string(),
+            ..VersionRecord::default()
+        }
+    }
+
+    pub fn from_assets(game_short_name: &str, version: &str, asset_names: &[&str]) -> Self {
+        let mut record = VersionRecord::new(version);
+        asset_names
+            .iter()
+            .for_each(|asset_name| record.add_asset(game_short_name, asset_name));
+        record
+    }
+
+    pub fn has_asset(&self, asset_name: &str) -> bool {
+        self.assets.iter().any(|asset| asset.name == asset_name)
+    }
+
+    pub fn add_asset(&mut self, game_short_name: &str, asset_name: &str) {
+        if !self.has_asset(asset_name) {
+            self.assets.push(VersionAsset {
+                name: asset_name.to_string(),
+                edition: Edition::from_asset_name(game_short_name, asset_name),
+            });
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, PartialEq, Default, Clone)]
 pub struct GameConfig {
     pub location: Option<String>,
     pub steamId: Option<SteamId>,
-    pub versions: Option<Vec<Vec<String>>>,
+    // latest version first
+    pub versions: Option<Vec<VersionRecord>>,
     pub version_in_use: Option<String>,
     pub nextgen: Option<bool>,
     pub runtime: Option<Runtime>,
     pub runArgs: Option<String>,
+    // release channel the game follows: "nightly", "stable" or "owner/repo". Without it `sources` from main section are used
+    pub channel: Option<String>,
+    // exact mod version or "current" - pinned games are skipped by update checks
+    pub pin: Option<String>,
+    // glob patterns of user files that mod install should not overwrite, added to the ones from main section
+    pub protected_paths: Option<Vec<String>>,
+    // glob pattern of release asset names of the game, default <GAME>.zip or <GAME>*.zip when game has both editions
+    pub asset_pattern: Option<String>,
+    // editions released for the game, overrides the built-in game registry
+    pub editions: Option<Vec<Edition>>,
+    // file name of game executable inside location, overrides the built-in game registry
+    pub executable: Option<String>,
+    // location registered by hand (non-Steam or custom install). Steam detection doesn't overwrite it
+    // and the game is launched by running its executable directly
+    pub manual: Option<bool>,
 }
 
 #[derive(Serialize, Deserialize, Debug, PartialEq, Default)]
 pub struct Main {
     // TODO maybe implement following -> pub deliberately_ask_for_update_check: Option<bool>,
+    // configs without it are migrated from schema 1 when loaded
+    pub schema_version: Option<u32>,
     pub sources: Option<Vec<String>>,
     pub repo_owner: Option<String>,
     pub repo_name: Option<String>,
+    // GitHub personal access token used for release checks and downloads. GITHUB_TOKEN env variable is used when not set
+    pub github_token: Option<String>,
+    // GitHub API base url, default https://api.github.com. i.e. GitHub Enterprise or a mirror
+    pub github_api_url: Option<String>,
+    // scheme and host (with optional path prefix) used instead of the one in asset download urls
+    pub asset_host: Option<String>,
     pub max_cache_versions_per_game: Option<u8>,
+    // limit of all cached mod archives together, oldest versions are removed first
+    pub max_cache_size_mb: Option<u64>,
+    // cached versions downloaded earlier than that are removed
+    pub max_cache_age_days: Option<u32>,
+    // how many mod assets are downloaded at the same time
+    pub max_parallel_downloads: Option<u8>,
+    // when true newer REFresher release is installed at startup, otherwise it is offered in main dialog
     pub autoupdate: Option<bool>,
     pub steamExePath: Option<String>,
+    // when true game shortcuts are also put into ~/.local/share/applications (Linux)
+    pub shortcuts_in_applications_menu: Option<bool>,
+    // steam ids of games to look for in Steam library, all games from game registry when not set
     pub steamGamesIdToSearchFor: Option<Vec<String>>,
     pub errorLevel: Option<ErrorLevel>,
+    // how many previous configs are kept as config.toml.bak.<N>, 0 disables backups
+    pub config_backups: Option<u8>,
+    // when true files of previous mod build that are missing from the new one are removed during update
+    pub clean_install: Option<bool>,
+    // glob patterns relative to game folder of user files that mod install should not overwrite
+    // when not set *_fw_config.txt and reframework/autorun are protected
+    pub protected_paths: Option<Vec<String>>,
 }
 
 pub type ShortGameName = String;
 pub type SteamId = String;
 
+// section which couldn't be read. It is written back as it was so user can fix it
+#[derive(Debug, PartialEq, Clone)]
+pub struct InvalidSection {
+    pub name: String,
+    pub content: Value,
+    pub error: String,
+}
+
 #[derive(Debug, Default)]
 pub struct REvilConfig {
     pub main: Main,
     pub games: HashMap<ShortGameName, GameConfig>,
+    pub invalid_sections: Vec<InvalidSection>,
 }
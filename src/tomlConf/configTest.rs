@@ -4,8 +4,15 @@ mod tests {
     use std::collections::HashMap;
 
     use crate::tomlConf::{
-        configStruct::{GameConfig, Main, REvilConfig, Runtime},
-        utils::{deserialize, serialize},
+        config::{
+            get_broken_config_path, get_config_backup_path, ConfigProvider, REvilConfigProvider,
+        },
+        configStruct::{
+            Edition, GameConfig, Main, PendingAction, REvilConfig, Runtime, VersionRecord,
+            CONFIG_SCHEMA_VERSION,
+        },
+        document::{get_config_value, set_config_value, update_document},
+        utils::{check, deserialize, serialize},
     };
 
     #[test]
@@ -33,8 +40,9 @@ mod tests {
         latawiec = true
     "#;
         match deserialize(content) {
-            Ok((main, games)) => {
+            Ok(REvilConfig { main, games, .. }) => {
                 let main_test = Main {
+                    schema_version: Some(CONFIG_SCHEMA_VERSION),
                     steamExePath: Some(r"C:\Program Files (x86)\Steam\steam.exe".to_owned()),
                     autoupdate: Some(true),
                     sources: Some(vec!["normal".to_owned(), "nightly".to_owned()]),
@@ -48,8 +56,15 @@ mod tests {
                     location: Some("D:/steam/games/RE2".to_owned()),
                     nextgen: Some(true),
                     runArgs: None,
+                    channel: None,
+                    pin: None,
+                    protected_paths: None,
+                    asset_pattern: None,
+                    editions: None,
+                    executable: None,
+                    manual: None,
                     runtime: Some(Runtime::OpenXR),
-                    versions: Some(vec![["v1.71-abd3145".to_owned()].to_vec()]),
+                    versions: Some(vec![VersionRecord::new("v1.71-abd3145")]),
                 };
                 games_test.insert("RE2".to_owned(), game);
 
@@ -58,10 +73,19 @@ mod tests {
                     location: Some("D:/steam/games/RE7".to_owned()),
                     nextgen: None,
                     runArgs: None,
+                    channel: None,
+                    pin: None,
+                    protected_paths: None,
+                    asset_pattern: None,
+                    editions: None,
+                    executable: None,
+                    manual: None,
                     runtime: None,
-                    versions: Some(vec![
-                        ["v1.71-abd3145".to_owned(), "RE7.zip".to_owned()].to_vec()
-                    ]),
+                    versions: Some(vec![VersionRecord::from_assets(
+                        "RE7",
+                        "v1.71-abd3145",
+                        &["RE7.zip"],
+                    )]),
                     steamId: None,
                 };
                 games_test.insert("RE7".to_owned(), game);
@@ -71,12 +95,19 @@ mod tests {
                     location: Some("D:/steam/games/RE12".to_owned()),
                     nextgen: None,
                     runArgs: None,
+                    channel: None,
+                    pin: None,
+                    protected_paths: None,
+                    asset_pattern: None,
+                    editions: None,
+                    executable: None,
+                    manual: None,
                     runtime: Some(Runtime::OpenVR),
-                    versions: Some(vec![[
-                        "v1.71-abd3145".to_owned(),
-                        "RE12_TDB77.zip".to_owned(),
-                    ]
-                    .to_vec()]),
+                    versions: Some(vec![VersionRecord::from_assets(
+                        "RE12",
+                        "v1.71-abd3145",
+                        &["RE12_TDB77.zip"],
+                    )]),
                     steamId: None,
                 };
                 games_test.insert("RE12".to_owned(), game);
@@ -91,6 +122,7 @@ mod tests {
     #[test]
     fn serialize_test() {
         let main_test = Main {
+            schema_version: Some(CONFIG_SCHEMA_VERSION),
             steamExePath: Some(r"C:\Program Files (x86)\Steam\steam.exe".to_owned()),
             autoupdate: Some(true),
             sources: Some(vec!["normal".to_owned(), "nightly".to_owned()]),
@@ -104,8 +136,15 @@ mod tests {
             location: Some("D:/steam/games/RE2".to_owned()),
             nextgen: Some(true),
             runArgs: None,
+            channel: None,
+            pin: None,
+            protected_paths: None,
+            asset_pattern: None,
+            editions: None,
+            executable: None,
+            manual: None,
             runtime: Some(Runtime::OpenXR),
-            versions: Some(vec![["v1.71-abd3145".to_owned()].to_vec()]),
+            versions: Some(vec![VersionRecord::new("v1.71-abd3145")]),
         };
         games_test.insert("RE2".to_owned(), game);
 
@@ -115,10 +154,19 @@ mod tests {
             location: Some("D:/steam/games/RE7".to_owned()),
             nextgen: None,
             runArgs: None,
+            channel: None,
+            pin: None,
+            protected_paths: None,
+            asset_pattern: None,
+            editions: None,
+            executable: None,
+            manual: None,
             runtime: None,
-            versions: Some(vec![
-                ["v1.71-abd3145".to_owned(), "RE7.zip".to_owned()].to_vec()
-            ]),
+            versions: Some(vec![VersionRecord::from_assets(
+                "RE7",
+                "v1.71-abd3145",
+                &["RE7.zip"],
+            )]),
         };
         games_test.insert("RE7".to_owned(), game);
 
@@ -128,27 +176,233 @@ mod tests {
             location: Some("D:/steam/games/RE12".to_owned()),
             nextgen: None,
             runArgs: None,
+            channel: None,
+            pin: None,
+            protected_paths: None,
+            asset_pattern: None,
+            editions: None,
+            executable: None,
+            manual: None,
             runtime: Some(Runtime::OpenVR),
-            versions: Some(vec![[
-                "v1.71-abd3145".to_owned(),
-                "RE12_TDB77.zip".to_owned(),
-            ]
-            .to_vec()]),
+            versions: Some(vec![VersionRecord::from_assets(
+                "RE12",
+                "v1.71-abd3145",
+                &["RE12_TDB77.zip"],
+            )]),
         };
         games_test.insert("RE12".to_owned(), game);
 
         let conf = REvilConfig {
             main: main_test,
             games: games_test,
+            ..REvilConfig::default()
         };
         let content = serialize(&conf).unwrap();
 
         match deserialize(&content) {
-            Ok((main, games)) => {
+            Ok(REvilConfig { main, games, .. }) => {
                 assert_eq!(conf.main, main);
                 assert_eq!(conf.games, games);
             }
             Err(_) => todo!(),
         };
     }
+
+    #[test]
+    fn old_version_arrays_are_migrated_to_records() {
+        let content = r#"
+        [main]
+        autoupdate = false
+
+        [RE2]
+        location = "D:/steam/games/RE2"
+        versions = [["switch", "RE2.zip"], ["v1.70-abd3145", "RE2.zip", "RE2_TDB.zip"], ["07ab146"]]
+        version_sources = [["v1.70-abd3145", "praydog/REFramework-nightly"]]
+        version_in_use = "v1.70-abd3145"
+        nextgen = true
+    "#;
+        let conf = deserialize(content).unwrap();
+        assert_eq!(conf.main.schema_version, Some(CONFIG_SCHEMA_VERSION));
+        let versions = conf.games.get("RE2").unwrap().versions.clone().unwrap();
+        assert_eq!(versions.len(), 3);
+        assert_eq!(versions[0].pending, Some(PendingAction::switch));
+        assert_eq!(versions[0].assets[0].edition, Some(Edition::nextgen));
+        assert_eq!(versions[1].version, "v1.70-abd3145");
+        assert_eq!(
+            versions[1].source.as_deref(),
+            Some("praydog/REFramework-nightly")
+        );
+        assert_eq!(versions[1].assets[1].name, "RE2_TDB.zip");
+        assert_eq!(versions[1].assets[1].edition, Some(Edition::standard));
+        assert_eq!(versions[2], VersionRecord::new("07ab146"));
+
+        // migrated config is written in the new schema and read back unchanged
+        let content = serialize(&conf).unwrap();
+        assert!(!content.contains("version_sources"));
+        let migrated = deserialize(&content).unwrap();
+        assert_eq!(conf.main, migrated.main);
+        assert_eq!(conf.games, migrated.games);
+    }
+
+    #[test]
+    fn invalid_sections_are_kept_and_reported() {
+        let content = r#"
+        [main]
+        autoupdate = true
+
+        [RE2]
+        location = "D:/steam/games/RE2"
+        nextgen = "yes"
+
+        [RE7]
+        location = "D:/steam/games/RE7"
+    "#;
+        let conf = deserialize(content).unwrap();
+        assert!(conf.games.contains_key("RE7"));
+        assert!(!conf.games.contains_key("RE2"));
+        assert_eq!(
+            check(content),
+            vec![
+                "[RE2] at line 5: invalid type: string \"yes\", expected a boolean for key `nextgen`"
+                    .to_string()
+            ]
+        );
+
+        // bad section is written back as it was
+        let content = serialize(&conf).unwrap();
+        let conf = deserialize(&content).unwrap();
+        assert_eq!(conf.invalid_sections.len(), 1);
+        assert_eq!(
+            conf.invalid_sections[0].content["nextgen"].as_str(),
+            Some("yes")
+        );
+        assert!(conf.games.contains_key("RE7"));
+
+        assert_eq!(
+            check("[main\nautoupdate = true"),
+            vec!["expected a right bracket, found a newline at line 1 column 6".to_string()]
+        );
+        assert!(check("[main]\nautoupdate = true").is_empty());
+    }
+
+    #[test]
+    fn broken_config_is_backed_up_before_it_gets_overwritten() {
+        let folder = std::env::temp_dir().join("refresher_broken_config");
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("config.toml");
+        std::fs::write(&path, "[main\nautoupdate = true").unwrap();
+        let provider = REvilConfigProvider::new(&path);
+
+        assert!(provider.load_from_file().is_err());
+        let backup_path = get_broken_config_path(path.to_str().unwrap());
+        assert_eq!(
+            std::fs::read_to_string(backup_path).unwrap(),
+            "[main\nautoupdate = true"
+        );
+        assert_eq!(provider.check_file().unwrap().len(), 1);
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn previous_configs_are_rotated_and_can_be_restored() {
+        let folder = std::env::temp_dir().join("refresher_config_backups");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("config.toml");
+        let filename = path.to_str().unwrap();
+        let provider = REvilConfigProvider::new(&path);
+        let config_with_token = |token: &str| REvilConfig {
+            main: Main {
+                config_backups: Some(2),
+                github_token: Some(token.to_string()),
+                ..Main::default()
+            },
+            ..REvilConfig::default()
+        };
+
+        for token in ["first", "second", "second", "third", "fourth"] {
+            provider.save_to_file(&config_with_token(token)).unwrap();
+        }
+        let token_of = |path: &str| {
+            deserialize(&std::fs::read_to_string(path).unwrap())
+                .unwrap()
+                .main
+                .github_token
+                .unwrap()
+        };
+        assert_eq!(token_of(filename), "fourth");
+        // saving the same content doesn't push out older backups
+        assert_eq!(token_of(&get_config_backup_path(filename, 1)), "third");
+        assert_eq!(token_of(&get_config_backup_path(filename, 2)), "second");
+        assert!(!std::path::Path::new(&get_config_backup_path(filename, 3)).exists());
+        assert!(!folder.join("config.toml.tmp").exists());
+
+        provider.restore_backup(2).unwrap();
+        assert_eq!(token_of(filename), "second");
+        assert_eq!(token_of(&get_config_backup_path(filename, 1)), "fourth");
+        assert!(provider.restore_backup(3).is_err());
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn saving_keeps_comments_and_order_of_unchanged_keys() {
+        let current = r#"# my REFresher settings
+[main]
+# checked on every start
+autoupdate = true
+steamExePath = "D:\\Steam" # moved Steam
+sources = [ "nightly" ]
+old_key = 1
+
+[RE2]
+location = "D:/steam/games/RE2"
+"#;
+        let mut conf = deserialize(current).unwrap();
+        conf.main.steamExePath = Some("E:\\Steam".to_string());
+        conf.games.insert(
+            "RE7".to_string(),
+            GameConfig {
+                location: Some("D:/steam/games/RE7".to_string()),
+                ..GameConfig::default()
+            },
+        );
+        let updated = update_document(current, &serialize(&conf).unwrap());
+        assert!(updated.starts_with(
+            r#"# my REFresher settings
+[main]
+# checked on every start
+autoupdate = true
+steamExePath = 'E:\Steam' # moved Steam
+sources = [ "nightly" ]
+"#
+        ));
+        assert!(!updated.contains("old_key"));
+        let updated_conf = deserialize(&updated).unwrap();
+        assert_eq!(updated_conf.main, conf.main);
+        assert_eq!(updated_conf.games, conf.games);
+        assert!(updated.find("[RE2]").unwrap() < updated.find("[RE7]").unwrap());
+    }
+
+    #[test]
+    fn config_values_are_validated_before_set() {
+        let content = r#"[main]
+autoupdate = true # comment stays
+
+[RE2]
+location = "D:/steam/games/RE2"
+"#;
+        let updated = set_config_value(content, "autoupdate", "false").unwrap();
+        assert!(updated.contains("autoupdate = false # comment stays"));
+        let updated = set_config_value(&updated, "RE2.runArgs", "-dx11").unwrap();
+        assert_eq!(get_config_value(&updated, "RE2.runArgs").unwrap(), "-dx11");
+        assert_eq!(
+            get_config_value(&updated, "main.autoupdate").unwrap(),
+            "false"
+        );
+
+        assert!(set_config_value(content, "autoupdate", "yes").is_err());
+        assert!(set_config_value(content, "max_cache_versions_per_game", "300").is_err());
+        assert!(set_config_value(content, "RE2.latawiec", "true").is_err());
+        assert!(get_config_value(content, "RE2.runArgs").is_err());
+    }
 }
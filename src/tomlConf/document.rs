@@ -0,0 +1,186 @@
+use error_stack::{IntoReport, Report, Result, ResultExt};
+use toml_edit::{Document, Item, Table};
+
+use super::{configStruct::ConfigError, utils::deserialize};
+
+type ConfigResult<T> = Result<T, ConfigError>;
+
+// updates current config content with the new one. Only changed keys are written so comments,
+// formatting and key order of the rest stay as user left them
+pub fn update_document(current: &str, new: &str) -> String {
+    let (mut current_doc, new_doc) = match (current.parse::<Document>(), new.parse::<Document>()) {
+        (Ok(current_doc), Ok(new_doc)) => (current_doc, new_doc),
+        _ => return new.to_string(),
+    };
+    let position = last_position(current_doc.as_table()) + 1;
+    merge_tables(current_doc.as_table_mut(), new_doc.as_table(), position);
+    current_doc.to_string()
+}
+
+// tables coming from the new content get position of their parent so they are written next to it
+fn merge_tables(current: &mut Table, new: &Table, position: usize) {
+    current.retain(|key, _| new.contains_key(key));
+    for (key, new_item) in new.iter() {
+        match current.get_mut(key) {
+            Some(Item::Table(current_table)) if new_item.is_table() => {
+                let position = current_table.position().unwrap_or(position);
+                merge_tables(current_table, new_item.as_table().unwrap(), position);
+            }
+            Some(current_item) => {
+                if to_plain_value(current_item) != to_plain_value(new_item) {
+                    replace_item(current_item, new_item, position);
+                }
+            }
+            None => {
+                let mut item = new_item.clone();
+                set_position(&mut item, position);
+                current.insert(key, item);
+            }
+        }
+    }
+}
+
+// trailing comment of a value is kept
+fn replace_item(current: &mut Item, new: &Item, position: usize) {
+    let decor = current.as_value().map(|value| value.decor().clone());
+    *current = new.clone();
+    set_position(current, position);
+    if let (Some(decor), Some(value)) = (decor, current.as_value_mut()) {
+        *value.decor_mut() = decor;
+    }
+}
+
+fn set_position(item: &mut Item, position: usize) {
+    let set_table_position = |table: &mut Table| {
+        table.set_position(position);
+        table
+            .iter_mut()
+            .for_each(|(_, child)| set_position(child, position));
+    };
+    match item {
+        Item::Table(table) => set_table_position(table),
+        Item::ArrayOfTables(tables) => tables.iter_mut().for_each(set_table_position),
+        _ => (),
+    }
+}
+
+fn last_position(table: &Table) -> usize {
+    table
+        .iter()
+        .flat_map(|(_, item)| match item {
+            Item::Table(table) => vec![table.position().unwrap_or_default(), last_position(table)],
+            Item::ArrayOfTables(tables) => tables
+                .iter()
+                .map(|table| {
+                    table
+                        .position()
+                        .unwrap_or_default()
+                        .max(last_position(table))
+                })
+                .collect(),
+            _ => Vec::new(),
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+// formatting independent value so i.e. [1,2] and [ 1, 2 ] are the same
+fn to_plain_value(item: &Item) -> Option<toml::Value> {
+    let mut document = Document::new();
+    document.insert("value", item.clone());
+    document
+        .to_string()
+        .parse::<toml::Value>()
+        .ok()?
+        .get("value")
+        .cloned()
+}
+
+// <section>.<key>, key without section belongs to [main]
+fn split_key(key: &str) -> (&str, &str) {
+    key.split_once('.').unwrap_or(("main", key))
+}
+
+fn parse_document(content: &str) -> ConfigResult<Document> {
+    content
+        .parse::<Document>()
+        .report()
+        .change_context(ConfigError::Deserializer)
+}
+
+pub fn get_config_value(content: &str, key: &str) -> ConfigResult<String> {
+    let document = parse_document(content)?;
+    let (section, key_name) = split_key(key);
+    let item = document
+        .get(section)
+        .and_then(|section| section.get(key_name))
+        .filter(|item| !item.is_none())
+        .ok_or_else(|| Report::new(ConfigError::UnknownKey(key.to_string())))?;
+    Ok(match item.as_str() {
+        Some(text) => text.to_string(),
+        None => match item.as_value() {
+            Some(value) => value.clone().decorated("", "").to_string(),
+            None => to_plain_value(item)
+                .and_then(|value| toml::to_string_pretty(&value).ok())
+                .unwrap_or_default(),
+        },
+    })
+}
+
+// value is read as TOML i.e. true, 3 or ["RE2*.zip"], anything else is taken as a string.
+// Returns new content when value is valid for Main or GameConfig
+pub fn set_config_value(content: &str, key: &str, value: &str) -> ConfigResult<String> {
+    let mut document = parse_document(content)?;
+    let (section, key_name) = split_key(key);
+    let new_value = format!("value = {}", value)
+        .parse::<Document>()
+        .ok()
+        .and_then(|parsed| parsed.get("value").and_then(Item::as_value).cloned())
+        .unwrap_or_else(|| value.into());
+    let table = document
+        .entry(section)
+        .or_insert(toml_edit::table())
+        .as_table_mut()
+        .ok_or_else(|| Report::new(ConfigError::UnknownKey(key.to_string())))?;
+    match table.get_mut(key_name).and_then(Item::as_value_mut) {
+        Some(current) => {
+            let decor = current.decor().clone();
+            *current = new_value;
+            *current.decor_mut() = decor;
+        }
+        None => {
+            table.insert(key_name, Item::Value(new_value));
+        }
+    };
+    let content = document.to_string();
+    validate_key(&content, section, key_name)?;
+    Ok(content)
+}
+
+// unknown keys are ignored by serde so a key is known when it survives reading and writing its section
+fn validate_key(content: &str, section: &str, key_name: &str) -> ConfigResult<()> {
+    let config = deserialize(content)?;
+    if let Some(invalid) = config
+        .invalid_sections
+        .iter()
+        .find(|invalid| invalid.name == section)
+    {
+        return Err(Report::new(ConfigError::InvalidValue(
+            invalid.error.to_string(),
+        )));
+    }
+    let section_value = match section {
+        "main" => toml::Value::try_from(&config.main).ok(),
+        _ => config
+            .games
+            .get(section)
+            .and_then(|game| toml::Value::try_from(game).ok()),
+    };
+    match section_value.and_then(|value| value.get(key_name).cloned()) {
+        Some(_) => Ok(()),
+        None => Err(Report::new(ConfigError::UnknownKey(format!(
+            "{}.{}",
+            section, key_name
+        )))),
+    }
+}
@@ -1,100 +1,257 @@
 use std::collections::HashMap;
 
 use error_stack::{IntoReport, Result, ResultExt};
-use toml::Value;
+use serde::Serialize;
+use toml::{value::Table, Value};
 
 use crate::{tomlConf::configStruct::ErrorLevel, MAX_ZIP_FILES_PER_GAME_CACHE};
 
 use super::{
-    configStruct::{ConfigError, GameConfig, Main, REvilConfig},
+    configStruct::{
+        ConfigError, GameConfig, InvalidSection, Main, PendingAction, REvilConfig, VersionRecord,
+        CONFIG_SCHEMA_VERSION,
+    },
     FromValue::FromValue,
 };
 
 type ConfigResult<T> = Result<T, ConfigError>;
 
+// schema 1 put these in place of the version of the latest versions entry
+static LEGACY_SWITCH_IDENTIFIER: &str = "switch";
+static LEGACY_UPDATE_IDENTIFIER: &str = "update_me";
+
+// section is wrapped in a table named after it so nested tables get [<name>.<key>] headers
+fn to_section_string<T: Serialize>(
+    name: &str,
+    section: &T,
+) -> std::result::Result<String, toml::ser::Error> {
+    let mut table = Table::new();
+    table.insert(name.to_string(), Value::try_from(section)?);
+    toml::to_string_pretty(&table)
+}
+
 pub fn serialize(config: &REvilConfig) -> ConfigResult<String> {
-    let main_table = toml::to_string_pretty(&config.main)
+    let invalid_section = |name: &str| {
+        config
+            .invalid_sections
+            .iter()
+            .find(|section| section.name == name)
+    };
+    // plain values have to be written before any table otherwise they would end up in the last one
+    let top_level: Table = config
+        .invalid_sections
+        .iter()
+        .filter(|section| !section.content.is_table())
+        .map(|section| (section.name.to_string(), section.content.clone()))
+        .collect();
+    let top_level = toml::to_string_pretty(&top_level)
         .report()
-        .change_context(ConfigError::Serializer)
-        .attach_printable_lazy(|| {
-            format!("Error during serialization of main {:?}", &config.main)
-        })?;
+        .change_context(ConfigError::Serializer)?;
 
-    let mut games_vec: Vec<_> = config.games.iter().collect();
-    games_vec.sort_by_key(|tuple| tuple.0.to_string());
+    let main_table = match invalid_section("main") {
+        Some(section) => to_section_string("main", &section.content),
+        None => to_section_string("main", &config.main),
+    }
+    .report()
+    .change_context(ConfigError::Serializer)
+    .attach_printable_lazy(|| format!("Error during serialization of main {:?}", &config.main))?;
 
-    let config_str = games_vec
+    let mut sections = config
+        .games
         .iter()
+        .filter(|(key, _)| invalid_section(key).is_none())
         .map(|(key, value)| {
-            let config_str = toml::to_string_pretty(&value)
+            Value::try_from(value)
                 .report()
                 .change_context(ConfigError::Serializer)
-                .attach_printable(format!(
-                    "err during serialization of key {} value {:?}",
-                    key, value
-                ))
-                .unwrap();
-            format!("[{}]\n{}\n", key, config_str)
+                .attach_printable_lazy(|| {
+                    format!("err during serialization of key {} value {:?}", key, value)
+                })
+                .map(|value| (key.as_str(), value))
         })
-        .reduce(|acc, config_str| format!("{}{}", acc, config_str));
-
-    let all = format!(
-        "[main]\n{}\n{}",
-        main_table,
-        config_str.ok_or("Reduce error").unwrap()
+        .collect::<ConfigResult<Vec<_>>>()?;
+    sections.extend(
+        config
+            .invalid_sections
+            .iter()
+            .filter(|section| section.name != "main" && section.content.is_table())
+            .map(|section| (section.name.as_str(), section.content.clone())),
     );
-    Ok(all)
+    sections.sort_by_key(|(key, _)| key.to_string());
+
+    let config_str = sections
+        .iter()
+        .map(|(key, value)| to_section_string(key, value).map(|str| format!("{}\n", str)))
+        .collect::<std::result::Result<String, _>>()
+        .report()
+        .change_context(ConfigError::Serializer)?;
+
+    Ok(format!("{}{}\n{}", top_level, main_table, config_str))
 }
 
-pub fn deserialize(content: &str) -> ConfigResult<(Main, HashMap<String, GameConfig>)> {
-    // TODO when there will be wrong toml syntax in config file for particular key then it will be treated
-    //      like config file error and all config.toml content will be altered with new content
-    //      not sure how to handle it differently -> priority very minor, it can be too much hassle I think. Also below I already handle some errors
-    let value = content
+pub fn deserialize(content: &str) -> ConfigResult<REvilConfig> {
+    let mut value = content
         .parse::<Value>()
         .report()
         .change_context(ConfigError::Deserializer)?;
+    if let Some(table) = value.as_table_mut() {
+        migrate(table);
+    }
 
     let table = match value.as_table() {
         Some(table) => table,
         None => return Err(ConfigError::Deserializer)?,
     };
 
-    let (_key, main_value) = match table.iter().find(|(s, _v)| s == &"main") {
-        Some((key, main_value)) => (key, main_value),
+    let main_value = match table.get("main") {
+        Some(main_value) => main_value,
         None => Err(ConfigError::Deserializer)
             .report()
             .attach_printable("Main not found!")?,
     };
+    let mut invalid_sections = Vec::new();
     let main = Value::from_value(main_value.to_owned()).unwrap_or_else(|err| {
-        eprintln!(
-            "Error during deserialization of toml main section {:#?} setting default for main",
-            err
-        );
+        invalid_sections.push(get_invalid_section(content, "main", main_value, err));
         Main {
+            schema_version: Some(CONFIG_SCHEMA_VERSION),
             max_cache_versions_per_game: Some(MAX_ZIP_FILES_PER_GAME_CACHE),
             errorLevel: Some(ErrorLevel::info),
             ..Main::default()
         }
     });
 
-    let games = table
-        .iter()
-        .filter_map(|(s, v)| {
-            if s == "main" {
-                return None;
+    let mut games = HashMap::new();
+    for (key, value) in table.iter().filter(|(key, _)| key.as_str() != "main") {
+        match Value::from_value::<GameConfig>(value.to_owned()) {
+            Ok(config) => {
+                games.insert(key.to_string(), config);
             }
-            let config = match Value::from_value(v.to_owned()) {
-                Ok(it) => it,
-                Err(err) => {
-                    eprintln!("Deserializer error - Reading {} game config {:#?} from toml \n toml::from_value error: {}", s, v, err);
-                    return None;
-                }
-            };
-            Some((s.to_string(), config))
-        })
-        .collect::<HashMap<String, GameConfig>>();
+            Err(err) => invalid_sections.push(get_invalid_section(content, key, value, err)),
+        };
+    }
+
+    Ok(REvilConfig {
+        main,
+        games,
+        invalid_sections,
+    })
+}
 
-    // games.iter().for_each(|game| println!("{:?}", game));
-    Ok((main, games))
+// problems found in config file content, empty when config is fine
+pub fn check(content: &str) -> Vec<String> {
+    // syntax error message already contains line and column
+    if let Err(err) = content.parse::<Value>() {
+        return vec![err.to_string()];
+    }
+    match deserialize(content) {
+        Ok(config) => config
+            .invalid_sections
+            .into_iter()
+            .map(|section| section.error)
+            .collect(),
+        Err(_) => vec!["[main] section not found".to_string()],
+    }
+}
+
+// toml reports key of a section error but not its line so the line of section header is added
+fn get_invalid_section(
+    content: &str,
+    name: &str,
+    value: &Value,
+    err: toml::de::Error,
+) -> InvalidSection {
+    let header = format!("[{}]", name);
+    let location = content
+        .lines()
+        .position(|line| line.trim() == header)
+        .map(|index| format!(" at line {}", index + 1))
+        .unwrap_or_default();
+    InvalidSection {
+        name: name.to_string(),
+        content: value.clone(),
+        error: format!("{}{}: {}", header, location, err),
+    }
+}
+
+// brings config of an older schema to the current one, nothing is lost on the way
+fn migrate(table: &mut Table) {
+    let schema_version = table
+        .get("main")
+        .and_then(|main| main.get("schema_version"))
+        .and_then(Value::as_integer)
+        .unwrap_or(1);
+    if schema_version >= CONFIG_SCHEMA_VERSION.into() {
+        return;
+    }
+    table
+        .iter_mut()
+        .filter(|(key, _)| key.as_str() != "main")
+        .for_each(|(short_name, game)| {
+            if let Some(game) = game.as_table_mut() {
+                migrate_versions(short_name, game);
+            }
+        });
+    if let Some(main) = table.get_mut("main").and_then(Value::as_table_mut) {
+        main.insert(
+            "schema_version".to_string(),
+            Value::Integer(CONFIG_SCHEMA_VERSION.into()),
+        );
+    }
+}
+
+// [version, asset names...] arrays and [version, source] pairs of version_sources become version records
+fn migrate_versions(short_name: &str, game: &mut Table) {
+    let to_strings = |value: &Value| -> Option<Vec<String>> {
+        value
+            .as_array()?
+            .iter()
+            .map(|item| item.as_str().map(String::from))
+            .collect()
+    };
+    let sources: HashMap<String, String> = game
+        .remove("version_sources")
+        .as_ref()
+        .and_then(Value::as_array)
+        .map(|pairs| {
+            pairs
+                .iter()
+                .filter_map(|pair| match to_strings(pair)?.as_slice() {
+                    [version, source] => Some((version.to_string(), source.to_string())),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let versions = match game.get_mut("versions").and_then(Value::as_array_mut) {
+        Some(it) => it,
+        None => return,
+    };
+    versions.iter_mut().for_each(|ver_set| {
+        let (version, asset_names) = match to_strings(ver_set) {
+            Some(strings) if !strings.is_empty() => {
+                let (version, asset_names) = strings.split_first().unwrap();
+                (version.to_string(), asset_names.to_vec())
+            }
+            // already a version record
+            _ => return,
+        };
+        let pending = match version.as_str() {
+            version if version == LEGACY_SWITCH_IDENTIFIER => Some(PendingAction::switch),
+            version if version == LEGACY_UPDATE_IDENTIFIER => Some(PendingAction::update),
+            _ => None,
+        };
+        let mut record = match pending {
+            // real version was overwritten by the identifier
+            Some(_) => VersionRecord::default(),
+            None => VersionRecord::new(&version),
+        };
+        record.pending = pending;
+        record.source = sources.get(&version).cloned();
+        asset_names
+            .iter()
+            .for_each(|asset_name| record.add_asset(short_name, asset_name));
+        if let Ok(value) = Value::try_from(record) {
+            *ver_set = value;
+        }
+    });
 }
@@ -4,19 +4,32 @@ pub mod unzip {
     use error_stack::Result;
     use error_stack::ResultExt;
     use log::debug;
+    use log::error;
     use log::info;
     use log::trace;
+    use log::warn;
+    use std::collections::HashMap;
     use std::ffi::OsStr;
     use std::fs;
     use std::io;
     use std::path::Path;
     use std::path::PathBuf;
+    use zip::read::ZipFile;
 
+    use crate::utils::protected_paths::ProtectedPaths;
+    use zip::ZipArchive;
+
+    pub static BACKUP_FOLDER: &str = "refr_install_backup";
+
+    // install is staged: every file that archive will overwrite is backed up first, then files are extracted.
+    // On success backup is removed (commit) otherwise backed up files are restored and new ones removed (rollback)
+    // existing files matching protected paths are kept untouched. Returns paths of extracted files
     pub fn unzip<F>(
         file_to_unzip: impl AsRef<Path>,
         destination: impl AsRef<Path>,
         should_skip_this_file: Option<F>,
-    ) -> Result<bool, UnzipError>
+        protected_paths: &ProtectedPaths,
+    ) -> Result<Vec<PathBuf>, UnzipError>
     where
         F: Fn(&OsStr) -> bool,
     {
@@ -25,6 +38,8 @@ pub mod unzip {
             file_to_unzip.as_ref().display(),
             destination.as_ref().display()
         );
+        verify_archive(&file_to_unzip)?;
+
         let file = fs::File::open(&file_to_unzip)
             .report()
             .change_context(UnzipError::other)?;
@@ -33,17 +48,87 @@ pub mod unzip {
             .report()
             .change_context(UnzipError::zip_new_err)?;
 
+        let mut transaction = InstallTransaction::new(destination.as_ref());
+        let result = transaction
+            .backup(&mut archive, &should_skip_this_file, protected_paths)
+            .and_then(|_| {
+                extract(
+                    &mut archive,
+                    destination.as_ref(),
+                    &should_skip_this_file,
+                    protected_paths,
+                    &mut transaction,
+                )
+            });
+        match result {
+            Ok(_) => Ok(transaction.commit()),
+            Err(err) => {
+                warn!(
+                    "Unzip of {} failed. Restoring previous files",
+                    file_to_unzip.as_ref().display()
+                );
+                transaction.rollback();
+                Err(err)
+            }
+        }
+    }
+
+    // returns path where the entry should be extracted or None when entry is skipped
+    fn entry_out_path<F>(
+        file: &ZipFile,
+        destination: &Path,
+        should_skip_this_file: &Option<F>,
+        protected_paths: &ProtectedPaths,
+    ) -> Result<Option<PathBuf>, UnzipError>
+    where
+        F: Fn(&OsStr) -> bool,
+    {
+        let outpath = match file.enclosed_name() {
+            Some(path) => path.to_owned(),
+            None => return Ok(None),
+        };
+        let final_path: PathBuf = destination.join(&outpath);
+
+        let name_to_check = if file.is_dir() {
+            OsStr::new(file.name())
+        } else {
+            final_path
+                .file_name()
+                .ok_or_else(|| Report::new(UnzipError::OutpathFileName))?
+        };
+        if let Some(ref fun) = should_skip_this_file {
+            if fun(name_to_check) {
+                trace!("Skip {:?} not extracted", name_to_check);
+                return Ok(None);
+            }
+        };
+        if !file.is_dir() && final_path.exists() && protected_paths.is_protected(&outpath) {
+            info!("Protected file {} kept", outpath.display());
+            return Ok(None);
+        }
+        Ok(Some(final_path))
+    }
+
+    fn extract<F>(
+        archive: &mut ZipArchive<fs::File>,
+        destination: &Path,
+        should_skip_this_file: &Option<F>,
+        protected_paths: &ProtectedPaths,
+        transaction: &mut InstallTransaction,
+    ) -> Result<(), UnzipError>
+    where
+        F: Fn(&OsStr) -> bool,
+    {
         for i in 0..archive.len() {
             let mut file = archive
                 .by_index(i)
                 .report()
                 .change_context(UnzipError::by_index_err)?;
-            let outpath = match file.enclosed_name() {
-                Some(path) => path.to_owned(),
-                None => continue,
-            };
-
-            let final_path: PathBuf = destination.as_ref().join(outpath);
+            let final_path =
+                match entry_out_path(&file, destination, should_skip_this_file, protected_paths)? {
+                    Some(path) => path,
+                    None => continue,
+                };
 
             {
                 let comment = file.comment();
@@ -52,31 +137,10 @@ pub mod unzip {
                 }
             }
 
-            if (*file.name()).ends_with('/') {
-                if let Some(ref fun) = should_skip_this_file {
-                    if fun(OsStr::new(file.name())) {
-                        trace!("Skip folder {:?} Folder not extracted", file.name());
-                        continue;
-                    }
-                };
-
+            if file.is_dir() {
                 trace!("File {} extracted to \"{}\"", i, final_path.display());
-                fs::create_dir_all(&final_path)
-                    .report()
-                    .change_context(UnzipError::create_dir_all)?;
+                transaction.create_dir_all(&final_path)?;
             } else {
-                let file_name = match final_path.file_name() {
-                    Some(it) => it,
-                    None => return Err(Report::new(UnzipError::OutpathFileName)),
-                };
-
-                if let Some(ref fun) = should_skip_this_file {
-                    if fun(file_name) {
-                        trace!("Skip File {:?} File not extracted", file_name);
-                        continue;
-                    }
-                };
-
                 trace!(
                     "File {} extracted to \"{}\" ({} bytes)",
                     i,
@@ -85,16 +149,14 @@ pub mod unzip {
                 );
 
                 if let Some(p) = final_path.parent() {
-                    if !p.exists() {
-                        fs::create_dir_all(&p)
-                            .report()
-                            .change_context(UnzipError::create_dir_all)?;
-                    }
+                    transaction.create_dir_all(p)?;
                 }
 
                 let mut outfile = fs::File::create(&final_path)
                     .report()
-                    .change_context(UnzipError::file_create)?;
+                    .change_context(UnzipError::file_create)
+                    .attach_printable_lazy(|| format!("{}", final_path.display()))?;
+                transaction.written_files.push(final_path.clone());
                 io::copy(&mut file, &mut outfile)
                     .report()
                     .change_context(UnzipError::io_copy)?;
@@ -112,8 +174,188 @@ pub mod unzip {
                 }
             }
         }
+        Ok(())
+    }
+
+    struct InstallTransaction {
+        backup_dir: PathBuf,
+        // original path -> backup path
+        backed_up: HashMap<PathBuf, PathBuf>,
+        written_files: Vec<PathBuf>,
+        created_dirs: Vec<PathBuf>,
+    }
+
+    impl InstallTransaction {
+        fn new(destination: &Path) -> Self {
+            InstallTransaction {
+                backup_dir: destination.join(BACKUP_FOLDER),
+                backed_up: HashMap::new(),
+                written_files: Vec::new(),
+                created_dirs: Vec::new(),
+            }
+        }
+
+        fn backup<F>(
+            &mut self,
+            archive: &mut ZipArchive<fs::File>,
+            should_skip_this_file: &Option<F>,
+            protected_paths: &ProtectedPaths,
+        ) -> Result<(), UnzipError>
+        where
+            F: Fn(&OsStr) -> bool,
+        {
+            if self.backup_dir.exists() {
+                warn!(
+                    "Removing leftover backup folder {}",
+                    self.backup_dir.display()
+                );
+                fs::remove_dir_all(&self.backup_dir)
+                    .report()
+                    .change_context(UnzipError::backup)?;
+            }
+            let destination = self.backup_dir.parent().unwrap().to_path_buf();
+            for i in 0..archive.len() {
+                let file = archive
+                    .by_index(i)
+                    .report()
+                    .change_context(UnzipError::by_index_err)?;
+                if file.is_dir() {
+                    continue;
+                }
+                let final_path = match entry_out_path(
+                    &file,
+                    &destination,
+                    should_skip_this_file,
+                    protected_paths,
+                )? {
+                    Some(path) if path.is_file() => path,
+                    _ => continue,
+                };
+                let backup_path = self
+                    .backup_dir
+                    .join(final_path.strip_prefix(&destination).unwrap());
+                if let Some(p) = backup_path.parent() {
+                    fs::create_dir_all(p)
+                        .report()
+                        .change_context(UnzipError::backup)?;
+                }
+                fs::copy(&final_path, &backup_path)
+                    .report()
+                    .change_context(UnzipError::backup)
+                    .attach_printable_lazy(|| {
+                        format!("Couldn't back up {}", final_path.display())
+                    })?;
+                trace!(
+                    "Backed up {} to {}",
+                    final_path.display(),
+                    backup_path.display()
+                );
+                self.backed_up.insert(final_path, backup_path);
+            }
+            debug!("Backed up {} files", self.backed_up.len());
+            Ok(())
+        }
+
+        // remembers folders which did not exist before so rollback can remove them
+        fn create_dir_all(&mut self, path: &Path) -> Result<(), UnzipError> {
+            let new_dirs: Vec<PathBuf> = path
+                .ancestors()
+                .take_while(|dir| !dir.exists())
+                .map(Path::to_path_buf)
+                .collect();
+            fs::create_dir_all(path)
+                .report()
+                .change_context(UnzipError::create_dir_all)?;
+            self.created_dirs.extend(new_dirs.into_iter().rev());
+            Ok(())
+        }
+
+        fn commit(self) -> Vec<PathBuf> {
+            if self.backup_dir.exists() {
+                fs::remove_dir_all(&self.backup_dir).unwrap_or_else(|err| {
+                    warn!(
+                        "Couldn't remove backup folder {} Err: {}",
+                        self.backup_dir.display(),
+                        err
+                    )
+                });
+            }
+            debug!(
+                "Install committed. Files written {}",
+                self.written_files.len()
+            );
+            self.written_files
+        }
 
-        Ok(true)
+        fn rollback(self) {
+            let mut restored_all = true;
+            self.written_files.iter().for_each(|path| {
+                let result = match self.backed_up.get(path) {
+                    Some(backup_path) => fs::copy(backup_path, path).map(|_| ()),
+                    None => fs::remove_file(path),
+                };
+                result.unwrap_or_else(|err| {
+                    restored_all = false;
+                    error!("Couldn't restore {} Err: {}", path.display(), err)
+                });
+            });
+            self.created_dirs.iter().rev().for_each(|dir| {
+                // only empty folders are removed, so nothing that was already there is lost
+                fs::remove_dir(dir).unwrap_or_else(|err| {
+                    debug!("Folder {} not removed Err: {}", dir.display(), err)
+                })
+            });
+
+            if !restored_all {
+                error!(
+                    "Not all files were restored. Backed up files are left in {}",
+                    self.backup_dir.display()
+                );
+                return;
+            }
+            if self.backup_dir.exists() {
+                fs::remove_dir_all(&self.backup_dir).unwrap_or_else(|err| {
+                    warn!(
+                        "Couldn't remove backup folder {} Err: {}",
+                        self.backup_dir.display(),
+                        err
+                    )
+                });
+            }
+            info!("Previous files restored");
+        }
+    }
+
+    // reads central directory and every entry so crc of each file is checked before anything is extracted
+    pub fn verify_archive(file_to_verify: impl AsRef<Path>) -> Result<(), UnzipError> {
+        let file = fs::File::open(&file_to_verify)
+            .report()
+            .change_context(UnzipError::other)?;
+        let mut archive = zip::ZipArchive::new(file)
+            .report()
+            .change_context(UnzipError::corrupted_archive)
+            .attach_printable_lazy(|| {
+                format!(
+                    "Central directory of {} can't be read",
+                    file_to_verify.as_ref().display()
+                )
+            })?;
+        for i in 0..archive.len() {
+            let mut file = archive
+                .by_index(i)
+                .report()
+                .change_context(UnzipError::corrupted_archive)?;
+            io::copy(&mut file, &mut io::sink())
+                .report()
+                .change_context(UnzipError::corrupted_archive)
+                .attach_printable_lazy(|| format!("Entry {} is corrupted", file.name()))?;
+        }
+        debug!(
+            "Archive {} verified. Files {}",
+            file_to_verify.as_ref().display(),
+            archive.len()
+        );
+        Ok(())
     }
 
     #[cfg(test)]
@@ -121,11 +363,27 @@ pub mod unzip {
         _file_to_unzip: impl AsRef<Path>,
         _destination: impl AsRef<Path>,
         _should_skip_this_file: Option<F>,
-    ) -> Result<bool, UnzipError>
+        _protected_paths: &ProtectedPaths,
+    ) -> Result<Vec<PathBuf>, UnzipError>
     where
         F: Fn(&OsStr) -> bool,
     {
-        Ok(true)
+        Ok(Vec::new())
+    }
+
+    // paths of all files in the archive relative to destination folder
+    pub fn list_archive_files(file: impl AsRef<Path>) -> Result<Vec<String>, UnzipError> {
+        let file = fs::File::open(&file)
+            .report()
+            .change_context(UnzipError::other)?;
+        let archive = zip::ZipArchive::new(file)
+            .report()
+            .change_context(UnzipError::zip_new_err)?;
+        Ok(archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(String::from)
+            .collect())
     }
 
     use std::error::Error;
@@ -142,6 +400,8 @@ pub mod unzip {
         file_create,
         io_copy,
         set_permissions,
+        corrupted_archive,
+        backup,
     }
 
     impl Display for UnzipError {
@@ -151,4 +411,113 @@ pub mod unzip {
     }
 
     impl Error for UnzipError {}
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn verify_archive_detects_truncated_zip() {
+            let valid = Path::new("src/tests/RE.zip");
+            assert!(verify_archive(valid).is_ok());
+
+            let bytes = fs::read(valid).unwrap();
+            let truncated = std::env::temp_dir().join("refresher_truncated_RE.zip");
+            fs::write(&truncated, &bytes[..bytes.len() / 2]).unwrap();
+            let result = verify_archive(&truncated);
+            fs::remove_file(&truncated).unwrap();
+
+            assert!(matches!(
+                result.unwrap_err().current_context(),
+                UnzipError::corrupted_archive
+            ));
+        }
+
+        fn no_skip(_: &OsStr) -> bool {
+            false
+        }
+
+        fn fresh_game_dir(name: &str) -> PathBuf {
+            let game_dir = std::env::temp_dir().join(name);
+            if game_dir.exists() {
+                fs::remove_dir_all(&game_dir).unwrap();
+            }
+            fs::create_dir_all(&game_dir).unwrap();
+            fs::write(game_dir.join("dinput8.dll"), "old").unwrap();
+            game_dir
+        }
+
+        #[test]
+        fn unzip_commits_install() {
+            let game_dir = fresh_game_dir("refresher_unzip_commit");
+
+            let written = unzip(
+                "src/tests/RE.zip",
+                &game_dir,
+                Some(no_skip),
+                &ProtectedPaths::default(),
+            )
+            .unwrap();
+
+            assert_eq!(written.len(), 16);
+            assert_ne!(
+                fs::read_to_string(game_dir.join("dinput8.dll")).unwrap(),
+                "old"
+            );
+            assert!(game_dir.join("reframework/autorun/re8_vr.lua").exists());
+            assert!(!game_dir.join(BACKUP_FOLDER).exists());
+            fs::remove_dir_all(&game_dir).unwrap();
+        }
+
+        #[test]
+        fn unzip_keeps_protected_files() {
+            let game_dir = fresh_game_dir("refresher_unzip_protected");
+            fs::create_dir_all(game_dir.join("reframework/autorun")).unwrap();
+            fs::write(game_dir.join("reframework/autorun/re8_vr.lua"), "mine").unwrap();
+            let protected_paths = ProtectedPaths::new(&["reframework/autorun".to_string()]);
+
+            let written = unzip(
+                "src/tests/RE.zip",
+                &game_dir,
+                Some(no_skip),
+                &protected_paths,
+            )
+            .unwrap();
+
+            assert_eq!(written.len(), 15);
+            assert_eq!(
+                fs::read_to_string(game_dir.join("reframework/autorun/re8_vr.lua")).unwrap(),
+                "mine"
+            );
+            assert!(game_dir
+                .join("reframework/autorun/re2_vr_melee.lua")
+                .exists());
+            fs::remove_dir_all(&game_dir).unwrap();
+        }
+
+        #[test]
+        fn unzip_rolls_back_on_error() {
+            let game_dir = fresh_game_dir("refresher_unzip_rollback");
+            // last entry of the archive can't be created as there is a folder with the same name
+            fs::create_dir_all(game_dir.join("reframework_revision.txt")).unwrap();
+
+            assert!(unzip(
+                "src/tests/RE.zip",
+                &game_dir,
+                Some(no_skip),
+                &ProtectedPaths::default(),
+            )
+            .is_err());
+
+            assert_eq!(
+                fs::read_to_string(game_dir.join("dinput8.dll")).unwrap(),
+                "old"
+            );
+            assert!(!game_dir.join("openvr_api.dll").exists());
+            assert!(!game_dir.join("reframework").exists());
+            assert!(game_dir.join("reframework_revision.txt").is_dir());
+            assert!(!game_dir.join(BACKUP_FOLDER).exists());
+            fs::remove_dir_all(&game_dir).unwrap();
+        }
+    }
 }
@@ -145,7 +145,7 @@ mod tests {
     use crate::utils::{binSearch::find_subsequence, init_logger::init_logger};
 
     fn init() {
-        init_logger("debug");
+        init_logger("debug", false);
     }
 
     // #[test]
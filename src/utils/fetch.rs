@@ -1,10 +1,75 @@
-use crate::reframework_github::release::ReleaseREFR;
+use std::{
+    fmt::{self, Display, Formatter},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::reframework_github::refr_github::REFRGithubError;
+use crate::reframework_github::release::{AssetsInfo, ReleaseREFR};
 use crate::DynResult;
-use log::error;
-use reqwest::header;
+use reqwest::{
+    blocking::Response,
+    header::{self, HeaderMap},
+    StatusCode,
+};
 use self_update::update::Release;
 
-pub fn fetch_release_api(github_api_url: &str) -> DynResult<Release> {
+pub static GITHUB_TOKEN_ENV: &str = "GITHUB_TOKEN";
+
+// values of X-RateLimit-* headers, reset is in UTC epoch seconds
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct RateLimit {
+    pub limit: Option<u64>,
+    pub remaining: Option<u64>,
+    pub reset: Option<u64>,
+}
+
+impl RateLimit {
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let value = |name: &str| -> Option<u64> {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse().ok())
+        };
+        RateLimit {
+            limit: value("x-ratelimit-limit"),
+            remaining: value("x-ratelimit-remaining"),
+            reset: value("x-ratelimit-reset"),
+        }
+    }
+}
+
+impl Display for RateLimit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "GitHub API rate limit exceeded")?;
+        if let Some(limit) = self.limit {
+            write!(f, " ({} requests per hour)", limit)?;
+        }
+        match self.reset {
+            Some(reset) => {
+                let now = SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|now| now.as_secs())
+                    .unwrap_or_default();
+                let minutes = reset.saturating_sub(now).div_ceil(60);
+                write!(
+                    f,
+                    ". Limit resets in {} min (unix time {}).",
+                    minutes, reset
+                )?;
+            }
+            None => write!(f, ".")?,
+        }
+        write!(
+            f,
+            " Set github_token in [main] section of config.toml or {} environment variable to raise the limit",
+            GITHUB_TOKEN_ENV
+        )
+    }
+}
+
+// token is optional, without it GitHub allows 60 requests per hour per IP
+pub fn github_headers(token: Option<&str>, accept: &str) -> DynResult<HeaderMap> {
     let mut headers = header::HeaderMap::new();
     headers.insert(
         header::USER_AGENT,
@@ -12,18 +77,58 @@ pub fn fetch_release_api(github_api_url: &str) -> DynResult<Release> {
             .parse()
             .expect("github invalid user-agent"),
     );
+    headers.insert(header::ACCEPT, accept.parse()?);
+    if let Some(token) = token {
+        headers.insert(
+            header::AUTHORIZATION,
+            format!("Bearer {}", token)
+                .parse()
+                .map_err(|_| "github token contains invalid characters")?,
+        );
+    }
+    Ok(headers)
+}
+
+// 429 or 403 with no remaining requests means rate limit, any other failure status is returned as is
+pub fn check_response_status(resp: &Response, url: &str) -> DynResult<()> {
+    let status = resp.status();
+    if status.is_success() {
+        return Ok(());
+    }
+    let rate_limit = RateLimit::from_headers(resp.headers());
+    if status == StatusCode::TOO_MANY_REQUESTS
+        || status == StatusCode::FORBIDDEN && rate_limit.remaining == Some(0)
+    {
+        return Err(Box::new(REFRGithubError::RateLimitExceeded(rate_limit)));
+    }
+    Err(format!("api request failed with status: {} - for: {}", status, url).into())
+}
+
+pub fn fetch_release_api(
+    github_api_url: &str,
+    token: Option<&str>,
+) -> DynResult<(Release, AssetsInfo)> {
+    let releases = fetch_releases_api(github_api_url, 1, 1, token)?;
+    let release = releases
+        .first()
+        .ok_or_else(|| "No release found".to_string())?;
+    Ok(release.clone())
+}
+
+pub fn fetch_releases_api(
+    github_api_url: &str,
+    page: u32,
+    per_page: u32,
+    token: Option<&str>,
+) -> DynResult<Vec<(Release, AssetsInfo)>> {
+    let headers = github_headers(token, "application/vnd.github+json")?;
 
     let resp = reqwest::blocking::Client::new()
         .get(github_api_url)
+        .query(&[("page", page), ("per_page", per_page)])
         .headers(headers)
         .send()?;
-    if !resp.status().is_success() {
-        error!(
-            "api request failed with status: {:?} - for: {:?}",
-            resp.status(),
-            github_api_url
-        )
-    }
+    check_response_status(&resp, github_api_url)?;
 
     let releases = resp.json::<serde_json::Value>()?;
     let releases = releases
@@ -31,11 +136,32 @@ pub fn fetch_release_api(github_api_url: &str) -> DynResult<Release> {
         .ok_or_else(|| "No releases found".to_string())?;
     let releases = releases
         .iter()
-        .take(1)
         .map(ReleaseREFR::from_release)
-        .collect::<DynResult<Vec<Release>>>()?;
-    let release = releases
-        .first()
-        .ok_or_else(|| "No release found".to_string())?;
-    Ok(release.clone())
+        .collect::<DynResult<Vec<(Release, AssetsInfo)>>>()?;
+    Ok(releases)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limit_from_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", "60".parse().unwrap());
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        headers.insert("x-ratelimit-reset", "1690000000".parse().unwrap());
+        let rate_limit = RateLimit::from_headers(&headers);
+        assert_eq!(
+            rate_limit,
+            RateLimit {
+                limit: Some(60),
+                remaining: Some(0),
+                reset: Some(1690000000)
+            }
+        );
+        assert!(rate_limit
+            .to_string()
+            .contains("Limit resets in 0 min (unix time 1690000000)"));
+    }
 }
@@ -0,0 +1,303 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use glob::{MatchOptions, Pattern};
+use log::warn;
+
+use crate::{
+    tomlConf::configStruct::{Edition, GameConfig, ShortGameName, SteamId},
+    STANDARD_TYPE_QUALIFIER,
+};
+
+type BuiltInGame = (&'static str, &'static str, &'static str, &'static [Edition]);
+
+// steam id, short name, executable and editions of games supported out of the box.
+// Game sections of config.toml can override them or add new games
+static BUILT_IN_GAMES: [BuiltInGame; 10] = [
+    ("601150", "DMC5", "DevilMayCry5.exe", &[Edition::nextgen]),
+    (
+        "1446780",
+        "MHRISE",
+        "MonsterHunterRise.exe",
+        &[Edition::nextgen],
+    ),
+    (
+        "883710",
+        "RE2",
+        "re2.exe",
+        &[Edition::nextgen, Edition::standard],
+    ),
+    (
+        "952060",
+        "RE3",
+        "re3.exe",
+        &[Edition::nextgen, Edition::standard],
+    ),
+    (
+        "418370",
+        "RE7",
+        "re7.exe",
+        &[Edition::nextgen, Edition::standard],
+    ),
+    ("1196590", "RE8", "re8.exe", &[Edition::nextgen]),
+    ("2050650", "RE4", "re4.exe", &[Edition::nextgen]),
+    ("1364780", "SF6", "StreetFighter6.exe", &[Edition::nextgen]),
+    ("2054970", "DD2", "DD2.exe", &[Edition::nextgen]),
+    (
+        "2246340",
+        "MHWILDS",
+        "MonsterHunterWilds.exe",
+        &[Edition::nextgen],
+    ),
+];
+
+static MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: false,
+    require_literal_leading_dot: false,
+};
+
+static GAME_REGISTRY: RwLock<Option<GameRegistry>> = RwLock::new(None);
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameEntry {
+    pub steam_id: SteamId,
+    pub short_name: ShortGameName,
+    // glob pattern of release asset names i.e. RE2*.zip matches both RE2.zip and RE2_TDB.zip
+    pub asset_pattern: String,
+    pub editions: Vec<Edition>,
+    // file name of game executable inside game folder, used to validate manually registered locations
+    pub executable: String,
+}
+
+impl GameEntry {
+    pub fn new(steam_id: &str, short_name: &str, editions: &[Edition]) -> Self {
+        GameEntry {
+            steam_id: steam_id.to_string(),
+            short_name: short_name.to_string(),
+            asset_pattern: get_default_asset_pattern(short_name, editions),
+            editions: editions.to_vec(),
+            executable: format!("{}.exe", short_name),
+        }
+    }
+
+    pub fn with_executable(mut self, executable: &str) -> Self {
+        self.executable = executable.to_string();
+        self
+    }
+
+    // only such games have separate Nextgen and Standard (TDB) assets, so the mod type can be switched
+    pub fn supports_both_editions(&self) -> bool {
+        self.editions.contains(&Edition::nextgen) && self.editions.contains(&Edition::standard)
+    }
+
+    pub fn matches_asset(&self, asset_name: &str) -> bool {
+        match Pattern::new(&self.asset_pattern) {
+            Ok(pattern) => pattern.matches_with(asset_name, MATCH_OPTIONS),
+            Err(err) => {
+                warn!(
+                    "Asset pattern {} of {} is invalid: {}",
+                    self.asset_pattern, self.short_name, err
+                );
+                false
+            }
+        }
+    }
+}
+
+// i.e. RE8.zip for a game with one edition and RE2*.zip for RE2.zip and RE2_TDB.zip
+fn get_default_asset_pattern(short_name: &str, editions: &[Edition]) -> String {
+    if editions.contains(&Edition::standard) {
+        format!("{}*.zip", short_name)
+    } else {
+        format!("{}.zip", short_name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameRegistry {
+    pub games: Vec<GameEntry>,
+}
+
+impl Default for GameRegistry {
+    fn default() -> Self {
+        GameRegistry {
+            games: BUILT_IN_GAMES
+                .iter()
+                .map(|(steam_id, short_name, executable, editions)| {
+                    GameEntry::new(steam_id, short_name, editions).with_executable(executable)
+                })
+                .collect(),
+        }
+    }
+}
+
+impl GameRegistry {
+    // built-in games with steamId, asset_pattern, editions and executable from game sections applied on top.
+    // Section of an unknown game adds it to the registry when it has steamId
+    pub fn from_config(games: &HashMap<ShortGameName, GameConfig>) -> Self {
+        let mut registry = GameRegistry::default();
+        let mut short_names: Vec<&ShortGameName> = games.keys().collect();
+        short_names.sort();
+        for short_name in short_names {
+            let game_config = &games[short_name];
+            let entry = match registry
+                .games
+                .iter_mut()
+                .find(|entry| &entry.short_name == short_name)
+            {
+                Some(entry) => entry,
+                None => match &game_config.steamId {
+                    Some(steam_id) => {
+                        registry.games.push(GameEntry::new(
+                            steam_id,
+                            short_name,
+                            &[Edition::nextgen],
+                        ));
+                        registry.games.last_mut().unwrap()
+                    }
+                    None => {
+                        warn!(
+                            "{} is not a known game and has no steamId, it is skipped",
+                            short_name
+                        );
+                        continue;
+                    }
+                },
+            };
+            if let Some(steam_id) = &game_config.steamId {
+                entry.steam_id = steam_id.to_string();
+            }
+            if let Some(editions) = &game_config.editions {
+                entry.editions = editions.clone();
+                entry.asset_pattern = get_default_asset_pattern(short_name, editions);
+            }
+            if let Some(asset_pattern) = &game_config.asset_pattern {
+                entry.asset_pattern = asset_pattern.to_string();
+            }
+            if let Some(executable) = &game_config.executable {
+                entry.executable = executable.to_string();
+            }
+        }
+        registry
+    }
+
+    pub fn find_by_steam_id(&self, steam_id: &str) -> Option<&GameEntry> {
+        self.games.iter().find(|entry| entry.steam_id == steam_id)
+    }
+
+    pub fn find_by_short_name(&self, short_name: &str) -> Option<&GameEntry> {
+        self.games
+            .iter()
+            .find(|entry| entry.short_name == short_name)
+    }
+
+    pub fn find_by_asset_name(&self, asset_name: &str) -> Option<&GameEntry> {
+        self.games
+            .iter()
+            .find(|entry| entry.matches_asset(asset_name))
+    }
+
+    pub fn supports_both_editions(&self, short_name: &str) -> bool {
+        self.find_by_short_name(short_name)
+            .map(GameEntry::supports_both_editions)
+            .unwrap_or_default()
+    }
+}
+
+// registry used by the whole program, it is set once config is loaded
+pub fn set_game_registry(registry: GameRegistry) {
+    *GAME_REGISTRY.write().unwrap_or_else(|err| err.into_inner()) = Some(registry);
+}
+
+// built-in registry is used until the one from config is set
+pub fn with_game_registry<T>(f: impl FnOnce(&GameRegistry) -> T) -> T {
+    let registry = GAME_REGISTRY.read().unwrap_or_else(|err| err.into_inner());
+    match registry.as_ref() {
+        Some(registry) => f(registry),
+        None => f(&GameRegistry::default()),
+    }
+}
+
+pub fn game_supports_both_editions(short_name: &str) -> bool {
+    with_game_registry(|registry| registry.supports_both_editions(short_name))
+}
+
+// short name of the game the asset belongs to. Asset unknown to the registry is named after its file
+// i.e. RE2_TDB.zip -> RE2, MyGame.zip -> MyGame
+pub fn get_short_name_from_asset_name(asset_name: &str) -> Option<ShortGameName> {
+    with_game_registry(|registry| {
+        registry
+            .find_by_asset_name(asset_name)
+            .map(|entry| entry.short_name.to_string())
+    })
+    .or_else(|| {
+        asset_name
+            .split_once(STANDARD_TYPE_QUALIFIER)
+            .or_else(|| asset_name.split_once(".zip"))
+            .map(|(short_name, _)| short_name.to_string())
+            .filter(|short_name| !short_name.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn assets_are_matched_with_built_in_games() {
+        let registry = GameRegistry::default();
+        let short_name = |asset_name: &str| {
+            registry
+                .find_by_asset_name(asset_name)
+                .map(|entry| entry.short_name.as_str())
+        };
+        assert_eq!(short_name("RE2_TDB.zip"), Some("RE2"));
+        assert_eq!(short_name("RE2.zip"), Some("RE2"));
+        assert_eq!(short_name("MHRISE.zip"), Some("MHRISE"));
+        assert_eq!(short_name("MHWILDS.zip"), Some("MHWILDS"));
+        assert_eq!(short_name("RE8_TDB.zip"), None);
+        assert!(registry.supports_both_editions("RE7"));
+        assert!(!registry.supports_both_editions("RE4"));
+        assert_eq!(
+            registry.find_by_short_name("MHRISE").unwrap().executable,
+            "MonsterHunterRise.exe"
+        );
+    }
+
+    #[test]
+    fn config_overrides_and_extends_registry() {
+        let mut games = HashMap::new();
+        games.insert(
+            "RE4".to_string(),
+            GameConfig {
+                editions: Some(vec![Edition::nextgen, Edition::standard]),
+                ..GameConfig::default()
+            },
+        );
+        games.insert(
+            "PRAGMATA".to_string(),
+            GameConfig {
+                steamId: Some("3357650".to_string()),
+                asset_pattern: Some("PRAGMATA*.zip".to_string()),
+                executable: Some("Pragmata.exe".to_string()),
+                ..GameConfig::default()
+            },
+        );
+        games.insert("UNKNOWN".to_string(), GameConfig::default());
+        let registry = GameRegistry::from_config(&games);
+
+        assert_eq!(registry.games.len(), BUILT_IN_GAMES.len() + 1);
+        assert!(registry.supports_both_editions("RE4"));
+        assert_eq!(
+            registry
+                .find_by_asset_name("RE4_TDB.zip")
+                .map(|entry| entry.short_name.as_str()),
+            Some("RE4")
+        );
+        let entry = registry.find_by_steam_id("3357650").unwrap();
+        assert_eq!(entry.short_name, "PRAGMATA");
+        assert!(entry.matches_asset("pragmata_demo.zip"));
+        assert_eq!(entry.executable, "Pragmata.exe");
+        assert!(registry.find_by_short_name("UNKNOWN").is_none());
+    }
+}
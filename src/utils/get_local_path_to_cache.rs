@@ -8,11 +8,20 @@ use std::path::PathBuf;
 
 use std::path::Path;
 
+pub static CACHE_FOLDER: &str = "refr_cache";
+
+pub static PARTIAL_DOWNLOAD_EXTENSION: &str = "part";
+
+// versions of games following their own release channel are kept in refr_cache/channels/<channel>
+pub static CHANNELS_CACHE_FOLDER: &str = "channels";
+
+// i.e. refr_cache/v1.333-07ab146 or refr_cache/channels/stable/v1.333 for a game with a channel
 pub fn get_local_path_to_cache_folder(
     release: Option<&Release>,
     ver: Option<&str>,
+    channel: Option<&str>,
 ) -> DynResult<PathBuf> {
-    trace!("release {:#?} ver {:?}", release, ver);
+    trace!("release {:#?} ver {:?} channel {:?}", release, ver, channel);
     let version: String;
     if let Some(ver) = ver {
         version = ver.to_string();
@@ -23,10 +32,67 @@ pub fn get_local_path_to_cache_folder(
         }
     }
 
-    let path = format!("refr_cache/{}", version);
+    let path = match channel {
+        Some(channel) => format!(
+            "{}/{}/{}/{}",
+            CACHE_FOLDER,
+            CHANNELS_CACHE_FOLDER,
+            get_channel_folder_name(channel),
+            version
+        ),
+        None => format!("{}/{}", CACHE_FOLDER, version),
+    };
     let path = Path::new(&path);
     let folders = Path::new(path);
     let mut path_buff = PathBuf::new();
     path_buff.push(folders);
     Ok(path_buff)
 }
+
+// channel may be a repo or a folder so every byte except letters, digits and `-` is written as _<hex>.
+// `_` is escaped too so two channels never share a folder
+pub fn get_channel_folder_name(channel: &str) -> String {
+    channel
+        .trim()
+        .bytes()
+        .map(|byte| match byte {
+            b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' | b'-' => (byte as char).to_string(),
+            _ => format!("_{:02X}", byte),
+        })
+        .collect()
+}
+
+// i.e. refr_cache/v1.333-07ab146/RE2.zip.part
+pub fn get_partial_download_path(path: &Path) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(PARTIAL_DOWNLOAD_EXTENSION);
+    path.with_file_name(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_versions_are_cached_separately() {
+        assert_eq!(
+            get_local_path_to_cache_folder(None, Some("v1.460-a1b2c3d"), None).unwrap(),
+            Path::new("refr_cache/v1.460-a1b2c3d")
+        );
+        assert_eq!(
+            get_local_path_to_cache_folder(None, Some("v1.5.9"), Some("someone/REFramework.fork"))
+                .unwrap(),
+            Path::new("refr_cache/channels/someone_2FREFramework_2Efork/v1.5.9")
+        );
+        assert_eq!(get_channel_folder_name("stable"), "stable");
+        assert_ne!(
+            get_channel_folder_name("someone/REFramework.fork"),
+            get_channel_folder_name("someone/REFramework_fork")
+        );
+        assert_ne!(
+            get_channel_folder_name("a/b_c"),
+            get_channel_folder_name("a_b/c")
+        );
+    }
+}
@@ -2,7 +2,8 @@ use log::*;
 use simplelog::*;
 use std::fs::File;
 
-pub fn init_logger(error_level: &str) {
+// with stderr_only terminal logs don't mix with output of the commands meant for other programs
+pub fn init_logger(error_level: &str, stderr_only: bool) {
     let config = ConfigBuilder::new()
         .set_level_color(Level::Info, Some(Color::Rgb(102, 212, 0)))
         .build();
@@ -11,7 +12,11 @@ pub fn init_logger(error_level: &str) {
         TermLogger::new(
             error_level.parse::<LevelFilter>().unwrap(),
             config,
-            TerminalMode::Mixed,
+            if stderr_only {
+                TerminalMode::Stderr
+            } else {
+                TerminalMode::Mixed
+            },
             ColorChoice::Auto,
         ),
         WriteLogger::new(
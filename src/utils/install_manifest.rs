@@ -0,0 +1,235 @@
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Component, Path, PathBuf},
+};
+
+use log::{debug, trace};
+
+use crate::DynResult;
+
+pub static MANIFESTS_FOLDER: &str = "refr_cache/manifests";
+
+// manifest lists every file (relative to the game folder) that has been written by mod installs, one path per line
+pub fn get_manifest_path(game_short_name: &str) -> PathBuf {
+    Path::new(MANIFESTS_FOLDER).join(format!("{}.txt", game_short_name))
+}
+
+pub fn load_manifest(manifest_path: &Path) -> DynResult<Option<Vec<String>>> {
+    if !manifest_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(manifest_path)?;
+    let files = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(String::from)
+        .collect();
+    Ok(Some(files))
+}
+
+pub fn save_manifest(manifest_path: &Path, files: &[String]) -> DynResult<()> {
+    if let Some(folder) = manifest_path.parent() {
+        fs::create_dir_all(folder)?;
+    }
+    let mut content = files.join("\n");
+    content.push('\n');
+    fs::write(manifest_path, content)?;
+    debug!(
+        "Manifest {} saved. Files {}",
+        manifest_path.display(),
+        files.len()
+    );
+    Ok(())
+}
+
+pub fn remove_manifest(manifest_path: &Path) -> DynResult<()> {
+    if manifest_path.exists() {
+        fs::remove_file(manifest_path)?;
+    }
+    Ok(())
+}
+
+pub fn to_relative_paths(location: &Path, files: &[PathBuf]) -> Vec<String> {
+    files
+        .iter()
+        .filter_map(|file| file.strip_prefix(location).ok())
+        .map(|file| {
+            file.components()
+                .map(|component| component.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+        })
+        .collect()
+}
+
+// adds written files to the manifest. In clean install mode manifest is replaced by written files
+// and files of the previous build which are missing from the new one are returned, so they can be removed
+pub fn update_manifest(
+    manifest_path: &Path,
+    written_files: &[String],
+    clean_install: bool,
+) -> DynResult<Vec<String>> {
+    let previous = load_manifest(manifest_path)?;
+    if written_files.is_empty() {
+        trace!(
+            "Nothing written. Manifest {} unchanged",
+            manifest_path.display()
+        );
+        return Ok(Vec::new());
+    }
+    let previous = previous.unwrap_or_default();
+    let written: HashSet<&String> = written_files.iter().collect();
+
+    let (files, obsolete) = if clean_install {
+        let obsolete: Vec<String> = previous
+            .iter()
+            .filter(|file| !written.contains(file))
+            .cloned()
+            .collect();
+        (written_files.to_vec(), obsolete)
+    } else {
+        let mut files = previous.clone();
+        let previous: HashSet<&String> = previous.iter().collect();
+        files.extend(
+            written_files
+                .iter()
+                .filter(|file| !previous.contains(file))
+                .cloned(),
+        );
+        (files, Vec::new())
+    };
+    save_manifest(manifest_path, &files)?;
+    Ok(obsolete)
+}
+
+// removes given files from game folder and then folders left empty. Returns number of removed files
+pub fn remove_installed_files(location: &Path, files: &[String]) -> DynResult<usize> {
+    let mut removed = 0;
+    let mut folders: HashSet<PathBuf> = HashSet::new();
+    for file in files {
+        let relative = Path::new(file);
+        // manifest is only allowed to point inside the game folder
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_)))
+        {
+            debug!("Skipping not allowed manifest entry {}", file);
+            continue;
+        }
+        let path = location.join(relative);
+        relative
+            .ancestors()
+            .skip(1)
+            .filter(|folder| !folder.as_os_str().is_empty())
+            .for_each(|folder| {
+                folders.insert(location.join(folder));
+            });
+        if path.is_file() {
+            fs::remove_file(&path)
+                .map_err(|err| format!("Couldn't remove {} Err: {}", path.display(), err))?;
+            trace!("Removed {}", path.display());
+            removed += 1;
+        }
+    }
+
+    // deepest folders first so parents can become empty
+    let mut folders: Vec<PathBuf> = folders.into_iter().collect();
+    folders.sort_by_key(|folder| std::cmp::Reverse(folder.components().count()));
+    folders.iter().for_each(|folder| {
+        if fs::remove_dir(folder).is_ok() {
+            trace!("Removed empty folder {}", folder.display());
+        }
+    });
+    Ok(removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn strings(files: &[&str]) -> Vec<String> {
+        files.iter().map(|file| file.to_string()).collect()
+    }
+
+    #[test]
+    fn update_manifest_merges_or_replaces_files() {
+        let manifest_path = std::env::temp_dir()
+            .join("refresher_manifest_test")
+            .join("RE2.txt");
+        remove_manifest(&manifest_path).unwrap();
+
+        let obsolete = update_manifest(
+            &manifest_path,
+            &strings(&["dinput8.dll", "reframework/autorun/old.lua"]),
+            false,
+        )
+        .unwrap();
+        assert!(obsolete.is_empty());
+
+        update_manifest(&manifest_path, &strings(&["openvr_api.dll"]), false).unwrap();
+        assert_eq!(
+            load_manifest(&manifest_path).unwrap().unwrap(),
+            strings(&[
+                "dinput8.dll",
+                "reframework/autorun/old.lua",
+                "openvr_api.dll"
+            ])
+        );
+
+        let obsolete = update_manifest(
+            &manifest_path,
+            &strings(&["dinput8.dll", "reframework/autorun/new.lua"]),
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            obsolete,
+            strings(&["reframework/autorun/old.lua", "openvr_api.dll"])
+        );
+        assert_eq!(
+            load_manifest(&manifest_path).unwrap().unwrap(),
+            strings(&["dinput8.dll", "reframework/autorun/new.lua"])
+        );
+        remove_manifest(&manifest_path).unwrap();
+    }
+
+    #[test]
+    fn remove_installed_files_keeps_other_files() {
+        let game_dir = std::env::temp_dir().join("refresher_manifest_uninstall");
+        if game_dir.exists() {
+            fs::remove_dir_all(&game_dir).unwrap();
+        }
+        fs::create_dir_all(game_dir.join("reframework/autorun/utility")).unwrap();
+        fs::create_dir_all(game_dir.join("reframework/data")).unwrap();
+        [
+            "dinput8.dll",
+            "re2.exe",
+            "reframework/autorun/utility/RE2.lua",
+            "reframework/data/user_settings.json",
+        ]
+        .iter()
+        .for_each(|file| fs::write(game_dir.join(file), "x").unwrap());
+
+        let removed = remove_installed_files(
+            &game_dir,
+            &strings(&[
+                "dinput8.dll",
+                "openvr_api.dll",
+                "reframework/autorun/utility/RE2.lua",
+                "../re2.exe",
+            ]),
+        )
+        .unwrap();
+
+        assert_eq!(removed, 2);
+        assert!(!game_dir.join("dinput8.dll").exists());
+        assert!(!game_dir.join("reframework/autorun").exists());
+        assert!(game_dir
+            .join("reframework/data/user_settings.json")
+            .exists());
+        assert!(game_dir.join("re2.exe").exists());
+        fs::remove_dir_all(&game_dir).unwrap();
+    }
+}
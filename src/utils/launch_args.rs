@@ -0,0 +1,81 @@
+// splits runArgs like a shell would, double quotes keep arguments with spaces together
+pub fn split_launch_args(text: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+    for c in text.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut arg));
+                    has_arg = false;
+                }
+            }
+            c => {
+                arg.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(arg);
+    }
+    args
+}
+
+// runArgs from game config followed by --game-args given for this run only
+pub fn combine_launch_args(run_args: Option<&str>, extra_args: Option<&str>) -> Vec<String> {
+    [run_args, extra_args]
+        .into_iter()
+        .flatten()
+        .flat_map(split_launch_args)
+        .collect()
+}
+
+// steam://run/<id>//<args>/ passes arguments to the game, they have to be percent-encoded
+pub fn get_steam_run_url(id: &str, args: &[String]) -> String {
+    if args.is_empty() {
+        return format!("steam://rungameid/{}", id);
+    }
+    let joined = args
+        .iter()
+        .map(|arg| match arg.contains(char::is_whitespace) {
+            true => format!("\"{}\"", arg),
+            false => arg.to_string(),
+        })
+        .collect::<Vec<String>>()
+        .join(" ");
+    let encoded: String = joined
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'=' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect();
+    format!("steam://run/{}//{}/", id, encoded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_args_and_extra_args_are_combined() {
+        let args = combine_launch_args(Some("-dx11  -config \"my cfg.ini\""), Some("-nosplash"));
+        assert_eq!(args, vec!["-dx11", "-config", "my cfg.ini", "-nosplash"]);
+        assert!(combine_launch_args(None, Some("  ")).is_empty());
+
+        assert_eq!(get_steam_run_url("883710", &[]), "steam://rungameid/883710");
+        assert_eq!(
+            get_steam_run_url("883710", &args),
+            "steam://run/883710//-dx11%20-config%20%22my%20cfg.ini%22%20-nosplash/"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 #[cfg(test)]
 use mockall::automock;
+#[cfg(windows)]
 use mslnk::ShellLink;
 
 use std::{
@@ -7,17 +8,21 @@ use std::{
     fmt::{self, Display, Formatter},
     fs,
     path::{Path, PathBuf},
+    process::Command,
 };
 
 use crate::{
     rManager::rManager_header::{REvilManagerError, ResultManagerErr},
     tomlConf::configStruct::Runtime,
-    DynResult, GAMES_NEXTGEN_SUPPORT, STANDARD_TYPE_QUALIFIER,
+    utils::game_registry::game_supports_both_editions,
+    DynResult, STANDARD_TYPE_QUALIFIER,
 };
 use error_stack::{IntoReport, Report, ResultExt};
 use log::{debug, warn};
 
 use super::binSearch::find_string_in_binary_file;
+#[cfg(not(windows))]
+use super::shortcut::create_desktop_entry;
 
 const VERSION_FILENAME: &str = "reframework_revision.txt";
 
@@ -33,13 +38,14 @@ pub struct LocalGameConfig {
 #[cfg_attr(test, automock)]
 pub trait LocalFiles {
     fn get_local_report_for_game(&self, game_path: &str, game_short_name: &str) -> LocalGameConfig;
-    fn create_ms_lnk(
+    fn create_shortcut(
         &self,
-        lnk_name: &PathBuf,
-        target: &PathBuf,
+        shortcut_path: &Path,
+        target: &Path,
         arguments: Option<String>,
     ) -> DynResult<()>;
     fn create_cache_dir(&self) -> ResultManagerErr<PathBuf>;
+    fn run_game_exe(&self, executable: &Path, args: &[String]) -> DynResult<()>;
 }
 
 pub fn create_tdb_string(game_short_name: &str) -> String {
@@ -65,15 +71,21 @@ impl LocalFiles for LocalProvider {
             nextgen: map_to_nextgen(game_path, game_short_name),
         }
     }
-    fn create_ms_lnk(
+    // ms-link on Windows, .desktop entry elsewhere
+    fn create_shortcut(
         &self,
-        lnk_name: &PathBuf,
-        target: &PathBuf,
+        shortcut_path: &Path,
+        target: &Path,
         arguments: Option<String>,
     ) -> DynResult<()> {
-        let mut sl = ShellLink::new(target)?;
-        sl.set_arguments(arguments);
-        sl.create_lnk(lnk_name)?;
+        #[cfg(windows)]
+        {
+            let mut sl = ShellLink::new(target)?;
+            sl.set_arguments(arguments);
+            sl.create_lnk(shortcut_path)?;
+        }
+        #[cfg(not(windows))]
+        create_desktop_entry(shortcut_path, target, arguments.as_deref())?;
         Ok(())
     }
 
@@ -81,7 +93,7 @@ impl LocalFiles for LocalProvider {
         let ms_links_folder = Path::new("REFR_links");
 
         fs::create_dir_all(&ms_links_folder).map_err(|err| {
-            Report::new(REvilManagerError::FailedToCreateMsLink(format!(
+            Report::new(REvilManagerError::FailedToCreateShortcut(format!(
                 "Error during create_dir_all path {} Err {}",
                 ms_links_folder.display(),
                 err
@@ -89,12 +101,29 @@ impl LocalFiles for LocalProvider {
         })?;
         Ok(ms_links_folder.to_path_buf())
     }
+
+    // for games registered manually, game folder is the working directory like when Steam launches it
+    fn run_game_exe(&self, executable: &Path, args: &[String]) -> DynResult<()> {
+        let game_folder = executable.parent().unwrap_or_else(|| Path::new("."));
+        Command::new(executable)
+            .args(args)
+            .current_dir(game_folder)
+            .spawn()
+            .map_err(|err| {
+                format!(
+                    "Error during launching {} Err {}",
+                    executable.display(),
+                    err
+                )
+            })?;
+        Ok(())
+    }
 }
 
 fn map_to_nextgen(path: impl AsRef<Path>, game_short_name: &str) -> Option<bool> {
     let dinput8_path = path.as_ref().join("dinput8.dll");
 
-    if GAMES_NEXTGEN_SUPPORT.contains(&game_short_name) {
+    if game_supports_both_editions(game_short_name) {
         let text = create_tdb_string(game_short_name);
         let is_standard_edition = match find_string_in_binary_file(&dinput8_path, &text) {
             Ok(it) => it,
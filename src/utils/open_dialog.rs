@@ -7,7 +7,7 @@ pub mod open_dialog {
     use dialoguer::theme::ColorfulTheme;
     use error_stack::{IntoReport, ResultExt};
 
-    use dialoguer::Select;
+    use dialoguer::{Input, Select};
 
     use crate::dialogs::dialogs::{DialogsErrors, ResultDialogsErr};
 
@@ -27,4 +27,13 @@ pub mod open_dialog {
             .change_context(DialogsErrors::OpenDialogError)?;
         Ok(selection)
     }
+
+    pub fn open_input(prompt: &str) -> ResultDialogsErr<String> {
+        let input: String = Input::with_theme(&ColorfulTheme::default())
+            .with_prompt(prompt)
+            .interact_text()
+            .report()
+            .change_context(DialogsErrors::OpenDialogError)?;
+        Ok(input)
+    }
 }
@@ -1,5 +1,11 @@
 use indicatif::ProgressStyle;
 
+pub fn getDownloadProgressStyle() -> ProgressStyle {
+    ProgressStyle::default_bar()
+        .template("{msg:32} [{bar:30.cyan/blue}] {bytes}/{total_bytes} ({eta})")
+        .progress_chars("#>-")
+}
+
 pub fn getProgressStyle() -> ProgressStyle {
     ProgressStyle::default_spinner()
         .template("{spinner:.blue} {msg}")
@@ -0,0 +1,99 @@
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+use log::warn;
+
+use crate::tomlConf::configStruct::{GameConfig, Main};
+
+pub static DEFAULT_PROTECTED_PATHS: [&str; 2] = ["*_fw_config.txt", "reframework/autorun"];
+
+static MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+// glob patterns relative to game folder. Pattern without `/` matches file or folder name at any level,
+// pattern which matches a folder protects everything inside of it
+#[derive(Debug, Default)]
+pub struct ProtectedPaths {
+    patterns: Vec<Pattern>,
+}
+
+impl ProtectedPaths {
+    pub fn new(patterns: &[String]) -> Self {
+        let patterns = patterns
+            .iter()
+            .filter_map(|pattern| {
+                Pattern::new(pattern.trim_matches('/'))
+                    .map_err(|err| warn!("Protected path pattern {} is invalid: {}", pattern, err))
+                    .ok()
+            })
+            .collect();
+        ProtectedPaths { patterns }
+    }
+
+    // main patterns (or defaults if not set) together with the ones set for the game
+    pub fn from_config(main: &Main, game_config: &GameConfig) -> Self {
+        let mut patterns: Vec<String> = main.protected_paths.clone().unwrap_or_else(|| {
+            DEFAULT_PROTECTED_PATHS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect()
+        });
+        if let Some(game_patterns) = &game_config.protected_paths {
+            patterns.extend(game_patterns.iter().cloned());
+        }
+        ProtectedPaths::new(&patterns)
+    }
+
+    pub fn is_protected(&self, relative_path: &Path) -> bool {
+        self.patterns.iter().any(|pattern| {
+            if pattern.as_str().contains('/') {
+                relative_path
+                    .ancestors()
+                    .any(|path| pattern.matches_path_with(path, MATCH_OPTIONS))
+            } else {
+                relative_path.iter().any(|name| {
+                    name.to_str()
+                        .map(|name| pattern.matches_with(name, MATCH_OPTIONS))
+                        .unwrap_or_default()
+                })
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_patterns_protect_user_files() {
+        let protected = ProtectedPaths::from_config(&Main::default(), &GameConfig::default());
+
+        assert!(protected.is_protected(Path::new("re2_fw_config.txt")));
+        assert!(protected.is_protected(Path::new("reframework/autorun/re2_vr_melee.lua")));
+        assert!(protected.is_protected(Path::new("reframework/autorun/utility/RE2.lua")));
+        assert!(!protected.is_protected(Path::new("dinput8.dll")));
+        assert!(!protected.is_protected(Path::new("reframework/plugins/plugin.dll")));
+        assert!(!protected.is_protected(Path::new("reframework_revision.txt")));
+    }
+
+    #[test]
+    fn game_patterns_extend_main_ones() {
+        let main = Main {
+            protected_paths: Some(vec!["openvr_api.dll".to_string()]),
+            ..Main::default()
+        };
+        let game_config = GameConfig {
+            protected_paths: Some(vec!["reframework/data/*.json".to_string()]),
+            ..GameConfig::default()
+        };
+        let protected = ProtectedPaths::from_config(&main, &game_config);
+
+        assert!(protected.is_protected(Path::new("openvr_api.dll")));
+        assert!(protected.is_protected(Path::new("reframework/data/settings.json")));
+        assert!(!protected.is_protected(Path::new("re2_fw_config.txt")));
+    }
+}
@@ -0,0 +1,121 @@
+use self_update::update::Release;
+
+use crate::DynResult;
+
+pub const RELEASES_PER_PAGE: u32 = 30;
+pub const MAX_RELEASE_PAGES: u32 = 10;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ReleaseSelector {
+    // position counted from the newest release i.e. 0 = latest, 1 = previous
+    Position(usize),
+    Tag(String),
+}
+
+impl From<&str> for ReleaseSelector {
+    fn from(text: &str) -> Self {
+        match text {
+            "latest" | "N" => ReleaseSelector::Position(0),
+            "previous" => ReleaseSelector::Position(1),
+            text => text
+                .strip_prefix("N-")
+                .and_then(|nb| nb.parse::<usize>().ok())
+                .map(ReleaseSelector::Position)
+                .unwrap_or_else(|| ReleaseSelector::Tag(text.to_string())),
+        }
+    }
+}
+
+impl ReleaseSelector {
+    // position of the release relative to the first release on the page that starts at page_offset
+    pub fn find_in_page<'a>(
+        &self,
+        releases: &'a [Release],
+        page_offset: usize,
+    ) -> Option<&'a Release> {
+        match self {
+            ReleaseSelector::Position(pos) => pos
+                .checked_sub(page_offset)
+                .and_then(|pos| releases.get(pos)),
+            ReleaseSelector::Tag(tag) => releases.iter().find(|release| {
+                release.name == *tag || release.version == tag.trim_start_matches('v')
+            }),
+        }
+    }
+}
+
+// goes through paginated releases till selector matches one of them
+pub fn find_release(
+    selector: &ReleaseSelector,
+    mut fetch_page: impl FnMut(u32, u32) -> DynResult<Vec<Release>>,
+) -> DynResult<Release> {
+    for page in 1..=MAX_RELEASE_PAGES {
+        let releases = fetch_page(page, RELEASES_PER_PAGE)?;
+        let page_offset = ((page - 1) * RELEASES_PER_PAGE) as usize;
+        if let Some(release) = selector.find_in_page(&releases, page_offset) {
+            return Ok(release.clone());
+        }
+        if releases.len() < RELEASES_PER_PAGE as usize {
+            break;
+        }
+    }
+    Err(format!("Release not found for {:?}", selector).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn releases(offset: usize, count: usize) -> Vec<Release> {
+        (offset..offset + count)
+            .map(|nb| Release {
+                name: format!("v1.{}-abc{}", 100 - nb, nb),
+                version: format!("nightly-{}", nb),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn selector_from_str() {
+        assert_eq!(
+            ReleaseSelector::from("latest"),
+            ReleaseSelector::Position(0)
+        );
+        assert_eq!(
+            ReleaseSelector::from("previous"),
+            ReleaseSelector::Position(1)
+        );
+        assert_eq!(ReleaseSelector::from("N-3"), ReleaseSelector::Position(3));
+        assert_eq!(
+            ReleaseSelector::from("N-x"),
+            ReleaseSelector::Tag("N-x".to_string())
+        );
+        assert_eq!(
+            ReleaseSelector::from("v1.99-abc1"),
+            ReleaseSelector::Tag("v1.99-abc1".to_string())
+        );
+    }
+
+    #[test]
+    fn find_release_through_pages() {
+        let fetch = |page: u32, per_page: u32| -> DynResult<Vec<Release>> {
+            let offset = ((page - 1) * per_page) as usize;
+            Ok(releases(offset, (per_page as usize).min(45 - offset)))
+        };
+
+        let release = find_release(&ReleaseSelector::from("previous"), fetch).unwrap();
+        assert_eq!(release.version, "nightly-1");
+
+        let release = find_release(&ReleaseSelector::from("N-32"), fetch).unwrap();
+        assert_eq!(release.version, "nightly-32");
+
+        let release = find_release(&ReleaseSelector::from("v1.60-abc40"), fetch).unwrap();
+        assert_eq!(release.version, "nightly-40");
+
+        let release = find_release(&ReleaseSelector::from("nightly-41"), fetch).unwrap();
+        assert_eq!(release.name, "v1.59-abc41");
+
+        assert!(find_release(&ReleaseSelector::from("N-50"), fetch).is_err());
+    }
+}
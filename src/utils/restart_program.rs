@@ -1,4 +1,4 @@
-use std::{io, process};
+use std::io;
 
 use std::process::Command;
 
@@ -7,13 +7,33 @@ use std::env;
 use crate::args::RunAfter;
 
 pub fn restart_program(run_after: RunAfter, short_name: String) -> io::Result<()> {
-    if cfg!(target_os = "windows") {
-        let path = env::current_exe()?;
-        Command::new(path)
-            .args(["-r", &format!("{:?}", run_after), "--one", &short_name])
-            .spawn()
-            .expect("failed to execute process");
-        process::exit(0);
+    restart_with_args(vec![
+        "-r".to_string(),
+        format!("{:?}", run_after),
+        "--one".to_string(),
+        short_name,
+    ])
+}
+
+// runs the new executable with arguments of this run, so nothing given on command line is lost
+pub fn restart_with_same_args() -> io::Result<()> {
+    restart_with_args(env::args().skip(1).collect())
+}
+
+fn restart_with_args(args: Vec<String>) -> io::Result<()> {
+    let path = env::current_exe()?;
+    let mut command = Command::new(path);
+    command.args(args);
+    // on Linux the new program replaces this one so it keeps the terminal
+    #[cfg(unix)]
+    let err: io::Error = {
+        use std::os::unix::process::CommandExt;
+        command.exec()
+    };
+    #[cfg(not(unix))]
+    let err: io::Error = {
+        command.spawn().expect("failed to execute process");
+        std::process::exit(0)
     };
-    Ok(())
+    Err(err)
 }
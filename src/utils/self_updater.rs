@@ -0,0 +1,250 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use log::debug;
+use self_update::{
+    update::{Release, ReleaseAsset},
+    version::bump_is_greater,
+};
+
+use crate::DynResult;
+
+// REFresher itself is released in this repo
+pub static SELF_REPO_OWNER: &str = "lukaskwkw";
+pub static SELF_REPO_NAME: &str = "reframework-updater";
+pub const SELF_UPDATE_RELEASES_TO_CHECK: u32 = 10;
+// downloaded executables are kept apart from mod versions in refr_cache/channels/<channel>
+pub static SELF_UPDATE_CHANNEL: &str = "lukaskwkw/reframework-updater";
+// previous executable is kept next to the new one as <name>.old so it can be restored by hand
+pub static BACKUP_EXTENSION: &str = "old";
+
+#[cfg(windows)]
+fn is_executable_name(name: &str) -> bool {
+    name.ends_with(".exe")
+}
+
+#[cfg(not(windows))]
+fn is_executable_name(name: &str) -> bool {
+    !name.contains('.')
+}
+
+// first bytes of an executable for this system, PE on Windows, Mach-O on macOS and ELF elsewhere
+#[cfg(windows)]
+static EXECUTABLE_MAGIC: &[&[u8]] = &[b"MZ"];
+#[cfg(target_os = "macos")]
+static EXECUTABLE_MAGIC: &[&[u8]] = &[
+    &[0xcf, 0xfa, 0xed, 0xfe],
+    &[0xce, 0xfa, 0xed, 0xfe],
+    &[0xca, 0xfe, 0xba, 0xbe],
+];
+#[cfg(all(not(windows), not(target_os = "macos")))]
+static EXECUTABLE_MAGIC: &[&[u8]] = &[b"\x7fELF"];
+
+// asset with target triple in its name wins, otherwise REFresher executable for this system
+pub fn find_executable_asset(release: &Release) -> Option<&ReleaseAsset> {
+    let target = self_update::get_target();
+    release
+        .assets
+        .iter()
+        .find(|asset| asset.name.contains(target))
+        .or_else(|| {
+            release.assets.iter().find(|asset| {
+                let name = asset.name.to_lowercase();
+                name.starts_with("refresher") && is_executable_name(&name)
+            })
+        })
+}
+
+// the greatest release version above current_version which has an executable to download
+pub fn find_newer_release(releases: Vec<Release>, current_version: &str) -> Option<Release> {
+    releases
+        .into_iter()
+        .filter(|release| find_executable_asset(release).is_some())
+        .filter(|release| bump_is_greater(current_version, &release.version).unwrap_or(false))
+        .fold(None, |newest, release| match &newest {
+            Some(newest_release)
+                if !bump_is_greater(&newest_release.version, &release.version).unwrap_or(false) =>
+            {
+                newest
+            }
+            _ => Some(release),
+        })
+}
+
+pub fn get_backup_path(executable: &Path) -> PathBuf {
+    let file_name = executable
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    executable.with_file_name(format!("{}.{}", file_name, BACKUP_EXTENSION))
+}
+
+// running executable can't be overwritten on Windows but it can be renamed, so it is moved aside first
+pub fn replace_executable(executable: &Path, new_executable: &Path) -> DynResult<PathBuf> {
+    let backup_path = get_backup_path(executable);
+    if backup_path.exists() {
+        fs::remove_file(&backup_path)?;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(new_executable, fs::Permissions::from_mode(0o755))?;
+    }
+    fs::rename(executable, &backup_path)?;
+    if let Err(err) = fs::rename(new_executable, executable) {
+        fs::rename(&backup_path, executable)?;
+        return Err(format!(
+            "Error during replacing {} Err {}",
+            executable.display(),
+            err
+        )
+        .into());
+    }
+    Ok(backup_path)
+}
+
+// an error page or a file of other system must never become REFresher executable
+pub fn check_executable_header(path: &Path) -> DynResult<()> {
+    let mut header = [0; 4];
+    let read = io::Read::read(&mut fs::File::open(path)?, &mut header)?;
+    if EXECUTABLE_MAGIC
+        .iter()
+        .any(|magic| header[..read].starts_with(magic))
+    {
+        return Ok(());
+    }
+    Err(format!("{} is not an executable for this system", path.display()).into())
+}
+
+// verified download is copied next to the executable first so the rename never crosses filesystems.
+// Returns backup path
+pub fn install_executable(executable: &Path, downloaded: &Path) -> DynResult<PathBuf> {
+    let new_executable = get_backup_path(executable).with_extension("new");
+    debug!(
+        "Copying {} to {}",
+        downloaded.display(),
+        new_executable.display()
+    );
+    let install = || -> DynResult<PathBuf> {
+        let copied = fs::copy(downloaded, &new_executable)?;
+        let expected = fs::metadata(downloaded)?.len();
+        if copied != expected {
+            return Err(format!(
+                "Size of {} is {} but expected {}",
+                new_executable.display(),
+                copied,
+                expected
+            )
+            .into());
+        }
+        check_executable_header(&new_executable)?;
+        replace_executable(executable, &new_executable)
+    };
+    install().inspect_err(|_| {
+        if new_executable.exists() {
+            fs::remove_file(&new_executable).unwrap_or_default()
+        }
+    })
+}
+
+// swaps the executable with the one kept by the last update, so rolling back can be undone the same way
+pub fn rollback_executable(executable: &Path) -> DynResult<PathBuf> {
+    let backup_path = get_backup_path(executable);
+    if !backup_path.exists() {
+        return Err(format!("Previous executable {} not found", backup_path.display()).into());
+    }
+    let swap_path = backup_path.with_extension("new");
+    fs::rename(executable, &swap_path)?;
+    if let Err(err) = fs::rename(&backup_path, executable) {
+        fs::rename(&swap_path, executable)?;
+        return Err(format!(
+            "Error during restoring {} Err {}",
+            backup_path.display(),
+            err
+        )
+        .into());
+    }
+    fs::rename(&swap_path, &backup_path)?;
+    Ok(backup_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+
+    fn release(version: &str, assets: &[&str]) -> Release {
+        Release {
+            version: version.to_string(),
+            assets: assets
+                .iter()
+                .map(|name| ReleaseAsset {
+                    name: name.to_string(),
+                    download_url: format!("url/{}", name),
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn newest_release_with_executable_is_picked() {
+        let executable = format!("REFresher-{}", self_update::get_target());
+        let releases = vec![
+            release("1.3.0", &["README.md"]),
+            release("1.2.0", &[&executable]),
+            release("1.2.1", &[&executable]),
+            release("1.1.0", &[&executable]),
+        ];
+        let newer = find_newer_release(releases.clone(), "1.1.5").unwrap();
+        assert_eq!(newer.version, "1.2.1");
+        assert_eq!(find_executable_asset(&newer).unwrap().name, executable);
+        assert!(find_newer_release(releases, "1.2.1").is_none());
+    }
+
+    #[test]
+    fn previous_executable_is_kept_as_backup() {
+        let folder = env::temp_dir().join("refresher_self_update");
+        fs::create_dir_all(&folder).unwrap();
+        let executable = folder.join("REFresher.exe");
+        let new_executable = folder.join("REFresher.exe.new");
+        fs::write(&executable, "old").unwrap();
+        fs::write(&new_executable, "new").unwrap();
+
+        let backup_path = replace_executable(&executable, &new_executable).unwrap();
+        assert_eq!(backup_path, folder.join("REFresher.exe.old"));
+        assert_eq!(fs::read_to_string(&executable).unwrap(), "new");
+        assert_eq!(fs::read_to_string(&backup_path).unwrap(), "old");
+        assert!(!new_executable.exists());
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn only_verified_executable_is_installed_and_can_be_rolled_back() {
+        let folder = env::temp_dir().join("refresher_self_update_install");
+        if folder.exists() {
+            fs::remove_dir_all(&folder).unwrap();
+        }
+        fs::create_dir_all(&folder).unwrap();
+        let executable = folder.join("REFresher.exe");
+        let downloaded = folder.join("downloaded");
+        fs::write(&executable, [EXECUTABLE_MAGIC[0], b"old"].concat()).unwrap();
+
+        fs::write(&downloaded, "<html>Bad gateway</html>").unwrap();
+        assert!(install_executable(&executable, &downloaded).is_err());
+        assert!(!folder.join("REFresher.exe.new").exists());
+        assert!(fs::read(&executable).unwrap().ends_with(b"old"));
+
+        fs::write(&downloaded, [EXECUTABLE_MAGIC[0], b"new"].concat()).unwrap();
+        let backup_path = install_executable(&executable, &downloaded).unwrap();
+        assert!(fs::read(&executable).unwrap().ends_with(b"new"));
+        assert!(fs::read(&backup_path).unwrap().ends_with(b"old"));
+
+        assert_eq!(rollback_executable(&executable).unwrap(), backup_path);
+        assert!(fs::read(&executable).unwrap().ends_with(b"old"));
+        assert!(fs::read(&backup_path).unwrap().ends_with(b"new"));
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::DynResult;
+
+// Windows gets ms-links, other systems freedesktop .desktop entries
+#[cfg(windows)]
+pub static SHORTCUT_EXTENSION: &str = "lnk";
+#[cfg(not(windows))]
+pub static SHORTCUT_EXTENSION: &str = "desktop";
+
+// remembers REFresher executable the shortcuts point to, so they are regenerated after it is moved
+pub static SHORTCUTS_TARGET_FILE: &str = ".target";
+
+pub fn get_shortcut_file_name(short_name: &str) -> String {
+    format!("REFR_{}.{}", short_name, SHORTCUT_EXTENSION)
+}
+
+// $XDG_DATA_HOME/applications or ~/.local/share/applications
+pub fn get_applications_folder() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .filter(|data_home| !data_home.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|data_home| data_home.join("applications"))
+}
+
+pub fn read_shortcuts_target(folder: &Path) -> Option<PathBuf> {
+    fs::read_to_string(folder.join(SHORTCUTS_TARGET_FILE))
+        .ok()
+        .map(|target| PathBuf::from(target.trim()))
+}
+
+pub fn save_shortcuts_target(folder: &Path, target: &Path) -> DynResult<()> {
+    fs::write(
+        folder.join(SHORTCUTS_TARGET_FILE),
+        target.display().to_string(),
+    )?;
+    Ok(())
+}
+
+// escaping of string values, see Desktop Entry Specification
+fn escape_string_value(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| match c {
+            '\\' => "\\\\".to_string(),
+            '\n' => "\\n".to_string(),
+            '\t' => "\\t".to_string(),
+            '\r' => "\\r".to_string(),
+            _ => c.to_string(),
+        })
+        .collect()
+}
+
+// Exec key arguments with reserved characters have to be quoted and `%` doubled so it is not a field code.
+// String value escaping is applied on top of quoting, so a backslash inside quotes ends up as four
+fn quote_exec_arg(arg: &str) -> String {
+    let arg = arg.replace('%', "%%");
+    if !arg.is_empty() && !arg.chars().any(|c| " \t\n\"'\\><~|&;$*?#()`".contains(c)) {
+        return arg;
+    }
+    let mut quoted = String::from('"');
+    arg.chars().for_each(|c| {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    });
+    quoted.push('"');
+    escape_string_value(&quoted)
+}
+
+pub fn create_desktop_entry(path: &Path, target: &Path, arguments: Option<&str>) -> DynResult<()> {
+    let name = path
+        .file_stem()
+        .map(|stem| {
+            stem.to_string_lossy()
+                .trim_start_matches("REFR_")
+                .to_string()
+        })
+        .unwrap_or_default();
+    let mut exec = quote_exec_arg(&target.display().to_string());
+    arguments
+        .into_iter()
+        .flat_map(str::split_whitespace)
+        .for_each(|arg| {
+            exec.push(' ');
+            exec.push_str(&quote_exec_arg(arg));
+        });
+    let working_dir = target.parent().unwrap_or_else(|| Path::new("."));
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=REFresher {name}\nComment=Update REFramework mod and launch {name}\nExec={}\nPath={}\nTerminal=true\nCategories=Game;\n",
+        exec,
+        escape_string_value(&working_dir.display().to_string()),
+    );
+    fs::write(path, entry)?;
+    // launchers refuse to start entries which are not executable
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn desktop_entry_runs_refresher_for_the_game() {
+        let folder = env::temp_dir().join("refresher_shortcuts");
+        fs::create_dir_all(&folder).unwrap();
+        let path = folder.join("REFR_RE2.desktop");
+        let target = Path::new("/home/user/My Games/REFresher");
+        create_desktop_entry(&path, target, Some("--one RE2")).unwrap();
+        let entry = fs::read_to_string(&path).unwrap();
+        assert!(entry.contains("Name=REFresher RE2\n"));
+        assert!(entry.contains("Exec=\"/home/user/My Games/REFresher\" --one RE2\n"));
+        assert!(entry.contains("Path=/home/user/My Games\n"));
+
+        save_shortcuts_target(&folder, target).unwrap();
+        assert_eq!(read_shortcuts_target(&folder), Some(target.to_path_buf()));
+        fs::remove_dir_all(&folder).unwrap();
+    }
+
+    #[test]
+    fn exec_args_escape_percent_and_backslash() {
+        assert_eq!(quote_exec_arg("--game-args=100%"), "--game-args=100%%");
+        assert_eq!(
+            quote_exec_arg("/games/50% off\\REFresher"),
+            "\"/games/50%% off\\\\\\\\REFresher\""
+        );
+        assert_eq!(
+            quote_exec_arg("say \"$HOME\""),
+            "\"say \\\\\"\\\\$HOME\\\\\"\""
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::{BTreeMap, HashMap};
+
+use serde::Serialize;
+
+use crate::{
+    reframework_github::release_source::get_game_channel,
+    tomlConf::configStruct::{REvilConfig, Runtime, ShortGameName},
+    utils::get_local_path_to_cache::get_local_path_to_cache_folder,
+    DynResult,
+};
+
+// machine readable summary of mod state per game, printed by `status --format json|toml`
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StatusReport {
+    pub latest_version: Option<String>,
+    pub games: BTreeMap<ShortGameName, GameStatus>,
+}
+
+// cached_versions has to be the last field as toml requires tables after plain values
+#[derive(Serialize, Debug, PartialEq)]
+pub struct GameStatus {
+    pub installed_version: Option<String>,
+    pub latest_version: Option<String>,
+    pub runtime: Option<Runtime>,
+    pub nextgen: Option<bool>,
+    pub pinned: Option<String>,
+    pub channel: Option<String>,
+    pub update_pending: bool,
+    pub cached_versions: Vec<CachedVersion>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct CachedVersion {
+    pub version: String,
+    pub source: Option<String>,
+    pub files: Vec<String>,
+}
+
+impl StatusReport {
+    // latest_version is the one of default release source, channel_versions has latest versions per game channel
+    pub fn new(
+        config: &REvilConfig,
+        games_that_require_update: &[ShortGameName],
+        latest_version: Option<&str>,
+        channel_versions: &HashMap<String, String>,
+    ) -> Self {
+        // sections only overriding the game registry are not games on this machine
+        let games = config
+            .games
+            .iter()
+            .filter(|(_, game_config)| game_config.location.is_some())
+            .map(|(short_name, game_config)| {
+                let channel = get_game_channel(game_config);
+                let cached_versions = game_config
+                    .versions
+                    .iter()
+                    .flatten()
+                    .filter_map(|record| {
+                        let cache_folder =
+                            get_local_path_to_cache_folder(None, Some(&record.version), channel)
+                                .ok()?;
+                        let files: Vec<String> = record
+                            .assets
+                            .iter()
+                            .filter(|asset| cache_folder.join(&asset.name).is_file())
+                            .map(|asset| asset.name.to_string())
+                            .collect();
+                        (!files.is_empty()).then(|| CachedVersion {
+                            version: record.version.to_string(),
+                            source: record.source.clone(),
+                            files,
+                        })
+                    })
+                    .collect();
+                let update_pending = games_that_require_update.contains(short_name);
+                let game_latest_version = match channel {
+                    Some(channel) => channel_versions.get(channel).cloned(),
+                    None => latest_version.map(String::from),
+                };
+                let game_status = GameStatus {
+                    installed_version: game_config.version_in_use.clone(),
+                    latest_version: game_latest_version,
+                    runtime: game_config.runtime.clone(),
+                    nextgen: game_config.nextgen,
+                    pinned: game_config.pin.clone(),
+                    channel: channel.map(String::from),
+                    update_pending,
+                    cached_versions,
+                };
+                (short_name.to_string(), game_status)
+            })
+            .collect();
+        StatusReport {
+            latest_version: latest_version.map(String::from),
+            games,
+        }
+    }
+
+    pub fn to_json(&self) -> DynResult<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    pub fn to_toml(&self) -> DynResult<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    pub fn to_text(&self) -> String {
+        let latest_version = self.latest_version.as_deref().unwrap_or("unknown");
+        let mut lines = vec![format!("Latest mod version: {}", latest_version)];
+        self.games.iter().for_each(|(short_name, game)| {
+            let mod_type = match game.nextgen {
+                Some(true) => "Nextgen",
+                Some(false) => "Standard",
+                None => "-",
+            };
+            let runtime = game
+                .runtime
+                .as_ref()
+                .map(|runtime| format!("{:?}", runtime))
+                .unwrap_or_else(|| "-".to_string());
+            let mut status = game
+                .installed_version
+                .clone()
+                .unwrap_or_else(|| "not installed".to_string());
+            if game.update_pending {
+                status = format!("{} <update available>", status);
+            }
+            if let Some(pin) = &game.pinned {
+                status = format!("{} <pinned {}>", status, pin);
+            }
+            if let Some(channel) = &game.channel {
+                status = format!(
+                    "{} <{} channel, latest {}>",
+                    status,
+                    channel,
+                    game.latest_version.as_deref().unwrap_or("unknown")
+                );
+            }
+            lines.push(format!(
+                "{} - {} - {} - {}",
+                short_name, mod_type, runtime, status
+            ));
+        });
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, fs};
+
+    use super::*;
+    use crate::tomlConf::configStruct::{Edition, GameConfig, VersionRecord};
+
+    #[test]
+    fn status_report_lists_cached_files_on_disk() {
+        let cache_folder =
+            get_local_path_to_cache_folder(None, Some("status_report_test"), None).unwrap();
+        fs::create_dir_all(&cache_folder).unwrap();
+        fs::write(cache_folder.join("RE2.zip"), "x").unwrap();
+
+        let mut games = HashMap::new();
+        games.insert(
+            "RE2".to_string(),
+            GameConfig {
+                location: Some("D:/Games/RE2".to_string()),
+                version_in_use: Some("status_report_test".to_string()),
+                versions: Some(vec![
+                    VersionRecord {
+                        source: Some("praydog/REFramework-nightly".to_string()),
+                        ..VersionRecord::from_assets(
+                            "RE2",
+                            "status_report_test",
+                            &["RE2.zip", "RE2_TDB.zip"],
+                        )
+                    },
+                    VersionRecord::from_assets("RE2", "older_version", &["RE2.zip"]),
+                ]),
+                nextgen: Some(true),
+                runtime: Some(Runtime::OpenXR),
+                ..GameConfig::default()
+            },
+        );
+        // section only overriding the registry entry of a game which isn't installed
+        games.insert(
+            "RE4".to_string(),
+            GameConfig {
+                editions: Some(vec![Edition::nextgen, Edition::standard]),
+                ..GameConfig::default()
+            },
+        );
+        let config = REvilConfig {
+            games,
+            ..REvilConfig::default()
+        };
+        let report = StatusReport::new(
+            &config,
+            &["RE2".to_string()],
+            Some("v1.71-abd3145"),
+            &HashMap::new(),
+        );
+        fs::remove_dir_all(&cache_folder).unwrap();
+
+        assert!(!report.games.contains_key("RE4"));
+        let game = &report.games["RE2"];
+        assert!(game.update_pending);
+        assert_eq!(
+            game.cached_versions,
+            vec![CachedVersion {
+                version: "status_report_test".to_string(),
+                source: Some("praydog/REFramework-nightly".to_string()),
+                files: vec!["RE2.zip".to_string()]
+            }]
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["games"]["RE2"]["runtime"], "OpenXR");
+        assert_eq!(json["games"]["RE2"]["latest_version"], "v1.71-abd3145");
+        let toml: toml::Value = toml::from_str(&report.to_toml().unwrap()).unwrap();
+        assert_eq!(toml["games"]["RE2"]["nextgen"].as_bool(), Some(true));
+        assert_eq!(
+            toml["games"]["RE2"]["cached_versions"][0]["files"][0].as_str(),
+            Some("RE2.zip")
+        );
+    }
+}
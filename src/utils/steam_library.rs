@@ -0,0 +1,231 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use log::{debug, warn};
+
+use crate::{tomlConf::configStruct::SteamId, DynResult};
+
+// Steam installation folders tried in order when steamExePath is not set
+static STEAM_ROOTS: [&str; 3] = [
+    ".steam/steam",
+    ".local/share/Steam",
+    ".var/app/com.valvesoftware.Steam/.local/share/Steam",
+];
+
+// (depth, key, value) of KeyValues (vdf, acf) text in file order. Nested blocks are flattened,
+// which is enough for libraryfolders.vdf and appmanifest_<id>.acf
+pub fn parse_key_values(text: &str) -> Vec<(usize, String, String)> {
+    let mut pairs = Vec::new();
+    let mut key: Option<String> = None;
+    let mut depth: usize = 0;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => {
+                let mut token = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '\\' => {
+                            if let Some(escaped) = chars.next() {
+                                token.push(escaped);
+                            }
+                        }
+                        '"' => break,
+                        _ => token.push(c),
+                    }
+                }
+                match key.take() {
+                    Some(key) => pairs.push((depth, key, token)),
+                    None => key = Some(token),
+                }
+            }
+            // key followed by a block has no value of its own
+            '{' => {
+                key = None;
+                depth += 1;
+            }
+            '}' => {
+                key = None;
+                depth = depth.saturating_sub(1);
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        break;
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+    pairs
+}
+
+// steamExePath can point either to Steam folder or to steam launcher, otherwise well known folders are checked
+pub fn find_steam_root(steam_path: Option<&Path>) -> Option<PathBuf> {
+    if let Some(steam_path) = steam_path {
+        let root = if steam_path.is_dir() {
+            Some(steam_path)
+        } else {
+            steam_path.parent()
+        };
+        if let Some(root) = root.filter(|root| root.join("steamapps").is_dir()) {
+            return Some(root.to_path_buf());
+        }
+        debug!(
+            "{} is not a Steam folder, looking in default locations",
+            steam_path.display()
+        );
+    }
+    let home = PathBuf::from(env::var_os("HOME")?);
+    STEAM_ROOTS
+        .iter()
+        .map(|root| home.join(root))
+        .find(|root| root.join("steamapps").is_dir())
+}
+
+// library folders from steamapps/libraryfolders.vdf, Steam folder itself is always the first one
+pub fn get_library_folders(steam_root: &Path) -> Vec<PathBuf> {
+    let mut folders = vec![steam_root.to_path_buf()];
+    let vdf_path = steam_root.join("steamapps").join("libraryfolders.vdf");
+    let text = match fs::read_to_string(&vdf_path) {
+        Ok(it) => it,
+        Err(err) => {
+            warn!("Can't read {} Err {}", vdf_path.display(), err);
+            return folders;
+        }
+    };
+    parse_key_values(&text)
+        .into_iter()
+        // "path" is used by current format, older one has library number as a key of top level block
+        .filter(|(depth, key, _)| key == "path" || *depth == 1 && key.parse::<u32>().is_ok())
+        .map(|(_, _, path)| PathBuf::from(path))
+        .for_each(|folder| {
+            if !folders.contains(&folder) {
+                folders.push(folder);
+            }
+        });
+    folders
+}
+
+fn get_install_dir(manifest_path: &Path) -> DynResult<String> {
+    let text = fs::read_to_string(manifest_path)?;
+    parse_key_values(&text)
+        .into_iter()
+        .find(|(_, key, _)| key.eq_ignore_ascii_case("installdir"))
+        .map(|(_, _, install_dir)| install_dir)
+        .ok_or_else(|| format!("installdir not found in {}", manifest_path.display()).into())
+}
+
+// locations of installed games with given ids based on appmanifest_<id>.acf files of all libraries
+pub fn find_game_locations(steam_root: &Path, game_ids: &[SteamId]) -> Vec<(SteamId, PathBuf)> {
+    let libraries = get_library_folders(steam_root);
+    game_ids
+        .iter()
+        .filter_map(|id| {
+            libraries.iter().find_map(|library| {
+                let steamapps = library.join("steamapps");
+                let manifest_path = steamapps.join(format!("appmanifest_{}.acf", id));
+                if !manifest_path.is_file() {
+                    return None;
+                }
+                let install_dir = match get_install_dir(&manifest_path) {
+                    Ok(it) => it,
+                    Err(err) => {
+                        warn!("{}", err);
+                        return None;
+                    }
+                };
+                let location = steamapps.join("common").join(install_dir);
+                location.is_dir().then(|| (id.to_string(), location))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn games_are_found_in_all_libraries() {
+        let root = env::temp_dir().join("refresher_steam_library");
+        if root.exists() {
+            fs::remove_dir_all(&root).unwrap();
+        }
+        let library = root.join("library");
+        fs::create_dir_all(root.join("steamapps/common/RESIDENT EVIL 2  BIOHAZARD RE2")).unwrap();
+        fs::create_dir_all(library.join("steamapps/common/Village")).unwrap();
+        fs::write(
+            root.join("steamapps/libraryfolders.vdf"),
+            format!(
+                r#""libraryfolders"
+{{
+    // comment
+    "0"
+    {{
+        "path"      "{}"
+        "apps" {{ "883710" "123" }}
+    }}
+    "1"
+    {{
+        "path"      "{}"
+    }}
+}}"#,
+                root.display(),
+                library.display()
+            ),
+        )
+        .unwrap();
+        fs::write(
+            root.join("steamapps/appmanifest_883710.acf"),
+            r#""AppState" { "appid" "883710" "installdir" "RESIDENT EVIL 2  BIOHAZARD RE2" }"#,
+        )
+        .unwrap();
+        fs::write(
+            library.join("steamapps/appmanifest_1196590.acf"),
+            "\"AppState\"\n{\n\t\"appid\"\t\t\"1196590\"\n\t\"installdir\"\t\t\"Village\"\n}",
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_library_folders(&root),
+            vec![root.clone(), library.clone()]
+        );
+        let ids = ["883710", "1196590", "418370"].map(String::from);
+        assert_eq!(
+            find_game_locations(&root, &ids),
+            vec![
+                (
+                    "883710".to_string(),
+                    root.join("steamapps/common/RESIDENT EVIL 2  BIOHAZARD RE2")
+                ),
+                (
+                    "1196590".to_string(),
+                    library.join("steamapps/common/Village")
+                ),
+            ]
+        );
+        assert_eq!(
+            find_steam_root(Some(&root.join("steam.sh"))),
+            Some(root.clone())
+        );
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn escaped_paths_are_parsed() {
+        let pairs = parse_key_values(
+            r#""LibraryFolders" { "1" "D:\\SteamLibrary" "ContentStatsID" "-1" }"#,
+        );
+        assert_eq!(
+            pairs,
+            vec![
+                (1, "1".to_string(), r"D:\SteamLibrary".to_string()),
+                (1, "ContentStatsID".to_string(), "-1".to_string())
+            ]
+        );
+    }
+}
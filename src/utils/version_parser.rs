@@ -12,10 +12,14 @@ fn get_version_tuple(repo: &str) -> Option<(&str, &str, &str)> {
 }
 
 pub fn isRepoVersionNewer(local: &str, repo: &str) -> Option<bool> {
-    let repo_version = get_version_tuple(repo).or_else(|| {
-        warn!("Repo version parser error: {}", repo);
-        None
-    })?;
+    let repo_version = match get_version_tuple(repo) {
+        Some(it) => it,
+        // i.e. tags of stable releases have no hash so any different tag is treated as newer
+        None => {
+            debug!("Repo version {} is not a nightly one, comparing tags", repo);
+            return Some(local != repo);
+        }
+    };
     let local_version: (&str, &str, &str) = match local.contains('.') {
         false => {
             debug!("Local version has only hash");